@@ -1,54 +1,77 @@
-use anyhow::{anyhow, Result};
-use aoc::aoc2022::get_prob_func;
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use iter_tools::Itertools;
-use std::fs;
-use std::fs::File;
-use std::io::Read;
+mod common;
 
-struct AocDef {
-    year: String,
-    day: String,
-    input: String,
-}
+use aoc::bench::check_day;
+use aoc::config::Config;
+use aoc::manifest::{changed_inputs, Kind};
+use aoc::solver::Registry;
+use aoc::store::InputFile;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 
-fn get_defs_to_run(dir: &str) -> Result<Vec<AocDef>> {
-    let des = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
-    let res = des
-        .into_iter()
-        .filter(|de| !de.file_name().into_string().unwrap().contains("-test.txt"))
-        .map(|de| {
-            let file_name = de.file_name().into_string().unwrap();
-            let (year, day) = file_name
-                .strip_suffix(".txt")
-                .ok_or_else(|| anyhow!("illegal suffix on file: {}", de.path().display()))?
-                .split_once("-")
-                .ok_or_else(|| anyhow!("illegal file name: {}", de.path().display()))?;
-            let mut f = File::open(de.path())?;
-            let mut input = String::new();
-            f.read_to_string(&mut input)?;
-            anyhow::Ok(AocDef {
-                year: year.to_string(),
-                day: day.to_string(),
-                input,
-            })
-        })
-        .collect::<Result<Vec<_>, _>>()?
+/// Benchmarks parsing and each part separately, the parts solving a pre-parsed input.
+/// Every year is a criterion group, so its report compares the phases of all days.
+fn aoc_benches(c: &mut Criterion) {
+    common::enable_allocs().unwrap();
+    let registry = Registry::new();
+    let store = Config::discover().unwrap().store();
+    for changed in changed_inputs(store.dir()) {
+        eprintln!("warning: {changed}");
+    }
+    let sel = common::selection().unwrap();
+    let (files, skipped) = store.files().unwrap();
+    for skipped in skipped {
+        eprintln!("note: {skipped}");
+    }
+    let files: Vec<InputFile> = files
         .into_iter()
-        .sorted_by_key(|d| format!("{}-{}", &d.year, &d.day))
+        .filter(|f| f.kind == Kind::Input)
+        .filter(|f| sel.is_none_or(|s| s.year == f.year && s.days.contains(f.day)))
         .collect();
-    Ok(res)
-}
-
-fn aoc_benches(c: &mut Criterion) {
-    let defs = get_defs_to_run("./inputs/").unwrap();
-    for AocDef { year, day, input } in defs {
-        for prob in ["1", "2"] {
-            let mut prob_func = get_prob_func(&format!("{}-{}", &day, prob));
-            c.bench_function(&format!("{}-{}-{}", &year, &day, prob), |b| {
-                b.iter(|| prob_func(black_box(&input)))
+    for files in files.chunk_by(|a, b| a.year == b.year) {
+        let year = files[0].year;
+        let mut group = c.benchmark_group(format!("aoc{year}"));
+        for file in files {
+            let day = file.day;
+            let solvers: Vec<_> = registry
+                .solvers()
+                .filter(|s| s.year() == year && s.day() == day)
+                .filter(|s| sel.is_none_or(|sel| sel.matches(*s)))
+                .collect();
+            if solvers.is_empty() {
+                eprintln!("note: skipping {}, there is no solver for it", file.name);
+                continue;
+            }
+            let input = match store.read_file(file) {
+                Ok(Some(input)) => input,
+                Ok(None) => continue,
+                Err(e) => {
+                    eprintln!("note: skipping {}: {e:#}", file.name);
+                    continue;
+                }
+            };
+            let checked = check_day(&solvers, &input);
+            common::print_allocs(&format!("{year}-{day}"), &checked.runs);
+            for run in checked.failed() {
+                eprintln!(
+                    "skipping {}-{}-{}: {}",
+                    year,
+                    day,
+                    run.solver.part(),
+                    run.outcome
+                );
+            }
+            let passed = checked.passed();
+            let (Some(first), Some(parsed)) = (passed.first(), &checked.parsed) else {
+                continue;
+            };
+            group.bench_function(BenchmarkId::new("parse", day), |b| {
+                b.iter_with_large_drop(|| first.parse(black_box(&input)))
             });
+            for solver in passed {
+                let id = BenchmarkId::new(format!("part{}", solver.part()), day);
+                group.bench_function(id, |b| b.iter(|| solver.solve_parsed(black_box(parsed))));
+            }
         }
+        group.finish();
     }
 }
 
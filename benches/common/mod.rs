@@ -0,0 +1,42 @@
+use anyhow::{Context, Result};
+use aoc::allocs::{self, Counting};
+use aoc::runner::{Run, Selection};
+use std::env;
+
+#[global_allocator]
+static ALLOC: Counting = Counting;
+
+/// Restricts the benchmarks to a selection like `2022`, `2022-7`, `2022-3..7` or `2022-7-2`.
+pub const SELECT_VAR: &str = "AOC_BENCH";
+
+pub fn selection() -> Result<Option<Selection>> {
+    env::var(SELECT_VAR)
+        .ok()
+        .map(|s| s.parse().with_context(|| format!("illegal {SELECT_VAR}")))
+        .transpose()
+}
+
+/// Set to count the allocations of the check run and print them with the benchmarks.
+pub const ALLOCS_VAR: &str = "AOC_ALLOCS";
+
+pub fn enable_allocs() -> Result<()> {
+    match env::var_os(ALLOCS_VAR) {
+        Some(_) => allocs::enable(),
+        None => Ok(()),
+    }
+}
+
+/// Prints the allocations counted for the parse and each part of a day's check run.
+pub fn print_allocs(name: &str, runs: &[Run]) {
+    let Some(first) = runs.first() else {
+        return;
+    };
+    if let Some(stats) = first.parse_allocs {
+        eprintln!("{name} parse allocations: {stats}");
+    }
+    for run in runs {
+        if let Some(stats) = run.allocs {
+            eprintln!("{name} part{} allocations: {stats}", run.solver.part());
+        }
+    }
+}
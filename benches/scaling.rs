@@ -0,0 +1,108 @@
+mod common;
+
+use anyhow::{Context, Result};
+use aoc::bench::check_day;
+use aoc::generate::growth;
+use aoc::solver::{Registry, Solver};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::env;
+use std::time::{Duration, Instant};
+
+/// Comma separated input sizes in bytes, e.g. `1000,1000000`.
+const SIZES_VAR: &str = "AOC_SCALING_SIZES";
+const DEFAULT_SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+
+/// Every size uses the same seed, so a run can be compared to earlier ones.
+const SEED: u64 = 2022;
+
+/// How long each phase is repeated to estimate its growth.
+const ESTIMATE_TIME: Duration = Duration::from_millis(50);
+
+fn sizes() -> Result<Vec<usize>> {
+    match env::var(SIZES_VAR) {
+        Ok(s) => s
+            .split(',')
+            .map(|n| {
+                n.trim()
+                    .parse()
+                    .with_context(|| format!("illegal {SIZES_VAR}"))
+            })
+            .collect(),
+        Err(_) => Ok(DEFAULT_SIZES.to_vec()),
+    }
+}
+
+/// The average time of `f`, repeated for at least [`ESTIMATE_TIME`].
+fn average(mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    let mut n = 0;
+    while n == 0 || start.elapsed() < ESTIMATE_TIME {
+        f();
+        n += 1;
+    }
+    start.elapsed() / n
+}
+
+/// Benchmarks every day with a generator against generated inputs of growing size, so
+/// criterion's reports show the growth curve of parsing and each part. The estimated
+/// exponent of that growth is printed after each day.
+fn scaling_benches(c: &mut Criterion) {
+    common::enable_allocs().unwrap();
+    let registry = Registry::new();
+    let sel = common::selection().unwrap();
+    let sizes = sizes().unwrap();
+    let solvers: Vec<&'static dyn Solver> = registry
+        .solvers()
+        .filter(|s| sel.is_none_or(|sel| sel.year == s.year() && sel.matches(*s)))
+        .collect();
+    for day in solvers.chunk_by(|a, b| (a.year(), a.day()) == (b.year(), b.day())) {
+        let (year, first) = (day[0].year(), day[0]);
+        let name = format!("{year}-{}", first.day());
+        if first.generate(SEED, 0).is_none() {
+            eprintln!("note: skipping {name}, it has no input generator");
+            continue;
+        }
+        let mut group = c.benchmark_group(format!("{name}-scaling"));
+        group.sample_size(10);
+        let phases: Vec<_> = std::iter::once("parse".to_string())
+            .chain(day.iter().map(|s| format!("part{}", s.part())))
+            .collect();
+        let mut samples = vec![vec![]; phases.len()];
+        for &size in &sizes {
+            let input = first.generate(SEED, size).expect("checked above");
+            let checked = check_day(day, &input);
+            common::print_allocs(&format!("{name} at {size} bytes"), &checked.runs);
+            if let Some(run) = checked.failed().next() {
+                eprintln!(
+                    "skipping {name} from {size} bytes on, part {}: {}",
+                    run.solver.part(),
+                    run.outcome
+                );
+                break;
+            }
+            let parsed = checked.parsed.expect("every part passed");
+            group.throughput(Throughput::Bytes(input.len() as u64));
+            group.bench_with_input(BenchmarkId::new("parse", size), &input, |b, inp| {
+                b.iter_with_large_drop(|| first.parse(black_box(inp)))
+            });
+            samples[0].push((input.len(), average(|| drop(first.parse(&input)))));
+            for (i, solver) in day.iter().enumerate() {
+                let id = BenchmarkId::new(&phases[i + 1], size);
+                group.bench_with_input(id, &parsed, |b, parsed| {
+                    b.iter(|| solver.solve_parsed(black_box(parsed)))
+                });
+                let time = average(|| drop(solver.solve_parsed(&parsed)));
+                samples[i + 1].push((input.len(), time));
+            }
+        }
+        group.finish();
+        for (phase, samples) in phases.iter().zip(&samples) {
+            if let Some(k) = growth(samples) {
+                eprintln!("{name} {phase}: time grows like size^{k:.2}");
+            }
+        }
+    }
+}
+
+criterion_group!(benches, scaling_benches);
+criterion_main!(benches);
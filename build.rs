@@ -0,0 +1,63 @@
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Generates one test per example in `inputs/` and part with a sidecar answer file.
+///
+/// Examples are named `YYYY-D-test.txt` or `YYYY-D-test-<name>.txt`, the expected answers
+/// live next to them as `YYYY-D-test[-<name>].part1` and `.part2`. Empty answer files are
+/// placeholders for answers that are not known yet and produce no test. The tests of a day
+/// without a module in `src/aocYYYY/` are ignored until it is added.
+fn main() {
+    let root = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let inputs = root.join("inputs");
+    println!("cargo:rerun-if-changed={}", inputs.display());
+
+    let mut examples: Vec<_> = fs::read_dir(&inputs)
+        .map(|rd| rd.flatten().map(|de| de.path()).collect())
+        .unwrap_or_default();
+    examples.sort();
+
+    let mut tests = String::new();
+    for example in examples {
+        let Some(stem) = example
+            .file_name()
+            .and_then(|f| f.to_str())
+            .and_then(|f| f.strip_suffix(".txt"))
+        else {
+            continue;
+        };
+        let Some((prob, name)) = stem.split_once("-test") else {
+            continue;
+        };
+        let Some((year, day)) = prob.split_once('-') else {
+            panic!("illegal example file name: {}", example.display());
+        };
+        let year_dir = root.join(format!("src/aoc{year}"));
+        println!("cargo:rerun-if-changed={}", year_dir.display());
+        let ignore = if year_dir.join(format!("day{day}.rs")).exists() {
+            String::new()
+        } else {
+            format!("#[ignore = \"{prob} has no solver yet\"]\n")
+        };
+        for part in ["1", "2"] {
+            let answer = example.with_file_name(format!("{stem}.part{part}"));
+            if fs::metadata(&answer).map_or(true, |m| m.len() == 0) {
+                continue;
+            }
+            let test_name = format!("y{year}_day{day}{name}_part{part}")
+                .replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+            writeln!(
+                tests,
+                "#[test]\n{ignore}fn {test_name}() {{\n    check({year}, {day}, \"{part}\", include_str!({:?}), include_str!({:?}));\n}}\n",
+                example.display().to_string(),
+                answer.display().to_string(),
+            )
+            .unwrap();
+        }
+    }
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("examples.rs");
+    fs::write(out, tests).unwrap();
+}
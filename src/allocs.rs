@@ -0,0 +1,177 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The allocations made during a [`measure`]ment.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize)]
+pub struct AllocStats {
+    pub allocations: u64,
+    /// Bytes requested, a reallocation counts with what it grew by.
+    pub bytes: u64,
+    /// The most bytes live at once, not counting what was live before.
+    pub peak: u64,
+}
+
+impl Display for AllocStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} / {} / {}",
+            self.allocations,
+            format_bytes(self.bytes),
+            format_bytes(self.peak)
+        )
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{bytes}B"),
+        1024..1_048_576 => format!("{:.1}KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1}MiB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+struct Counters {
+    allocations: u64,
+    bytes: u64,
+    live: i64,
+    peak: i64,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// The counters of the measurement running on this thread, if any.
+    static COUNTERS: Cell<Option<Counters>> = const { Cell::new(None) };
+}
+
+fn record(live: i64, bytes: u64, allocations: u64) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    // the counters are gone while the thread shuts down, which is not worth a panic
+    let _ = COUNTERS.try_with(|counters| {
+        if let Some(mut c) = counters.get() {
+            c.allocations += allocations;
+            c.bytes += bytes;
+            c.live += live;
+            c.peak = c.peak.max(c.live);
+            counters.set(Some(c));
+        }
+    });
+}
+
+/// A global allocator counting the allocations of [`measure`]d code. Binaries opt in by
+/// installing it with `#[global_allocator]` and calling [`enable`], until then it only
+/// adds an atomic load to every allocation.
+pub struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size() as i64, layout.size() as u64, 1);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size() as i64, layout.size() as u64, 1);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(-(layout.size() as i64), 0, 0);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            let grown = new_size as i64 - layout.size() as i64;
+            record(grown, grown.max(0) as u64, 1);
+        }
+        new
+    }
+}
+
+/// Turns counting on, failing unless [`Counting`] is the global allocator.
+pub fn enable() -> Result<()> {
+    ENABLED.store(true, Ordering::Relaxed);
+    let (_, stats) = measure(|| drop(black_box(Box::new(0_u64))));
+    if stats.is_some_and(|s| s.allocations > 0) {
+        Ok(())
+    } else {
+        ENABLED.store(false, Ordering::Relaxed);
+        Err(anyhow!(
+            "can't count allocations, `Counting` is not the global allocator"
+        ))
+    }
+}
+
+/// Runs `f` and counts the allocations it makes on this thread, `None` unless counting
+/// was [`enable`]d. Measurements may be nested.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return (f(), None);
+    }
+    let outer = COUNTERS.with(|c| c.replace(Some(Counters::default())));
+    let res = f();
+    let inner = COUNTERS.with(|c| c.get()).unwrap_or_default();
+    COUNTERS.with(|c| {
+        c.set(outer.map(|o| Counters {
+            allocations: o.allocations + inner.allocations,
+            bytes: o.bytes + inner.bytes,
+            live: o.live + inner.live,
+            peak: o.peak.max(o.live + inner.peak),
+        }))
+    });
+    let stats = AllocStats {
+        allocations: inner.allocations,
+        bytes: inner.bytes,
+        peak: inner.peak.max(0) as u64,
+    };
+    (res, Some(stats))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{enable, format_bytes, measure, Counting};
+
+    #[global_allocator]
+    static ALLOC: Counting = Counting;
+
+    #[test]
+    fn counts_allocations() {
+        enable().unwrap();
+        let (_, stats) = measure(|| {
+            let mut v = Vec::<u8>::with_capacity(100);
+            v.resize(300, 0);
+            let (_, inner) = measure(|| drop(vec![0_u8; 1000]));
+            assert_eq!(
+                inner.map(|s| (s.allocations, s.bytes, s.peak)),
+                Some((1, 1000, 1000))
+            );
+            v
+        });
+        let stats = stats.unwrap();
+        assert_eq!((stats.allocations, stats.bytes), (3, 1300));
+        assert_eq!(stats.peak, 1300);
+    }
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(512), "512B");
+        assert_eq!(format_bytes(1536), "1.5KiB");
+        assert_eq!(format_bytes(3 * 1_048_576), "3.0MiB");
+    }
+}
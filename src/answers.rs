@@ -0,0 +1,112 @@
+use crate::runner::{Outcome, Run};
+use crate::solver::{Day, Part, Year};
+use anyhow::{anyhow, Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+pub const ANSWERS_DIR: &str = "answers";
+
+/// The accepted answers of a year, read from `answers/YYYY.toml`.
+#[derive(Debug, Default)]
+pub struct Answers(HashMap<(Day, Part), String>);
+
+impl Answers {
+    pub fn path(dir: &Path, year: Year) -> PathBuf {
+        dir.join(format!("{year}.toml"))
+    }
+
+    /// Loads the answers of a year, an absent file means no recorded answers.
+    pub fn load(dir: &Path, year: Year) -> Result<Self> {
+        let pth = Self::path(dir, year);
+        let s = match fs::read_to_string(&pth) {
+            Ok(s) => s,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        s.parse()
+            .with_context(|| format!("illegal answers file {}", pth.display()))
+    }
+
+    pub fn get(&self, day: Day, part: Part) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+}
+
+impl std::str::FromStr for Answers {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let raw: BTreeMap<String, BTreeMap<String, String>> = toml::from_str(s)?;
+        let mut answers = HashMap::new();
+        for (day, parts) in raw {
+            let day: Day = day.parse()?;
+            for (part, answer) in parts {
+                let part: Part = part.parse()?;
+                if answers.insert((day, part), answer).is_some() {
+                    return Err(anyhow!("duplicate answer for day {day} part {part}"));
+                }
+            }
+        }
+        Ok(Self(answers))
+    }
+}
+
+pub enum Verdict<'a> {
+    Pass,
+    Fail {
+        expected: &'a str,
+        actual: &'a str,
+    },
+    Missing {
+        actual: &'a str,
+    },
+    NoInput,
+    /// The solver failed, panicked or timed out.
+    Error(&'a Outcome),
+}
+
+pub fn verify<'a>(answers: &'a Answers, run: &'a Run) -> Verdict<'a> {
+    let expected = answers.get(run.solver.day(), run.solver.part());
+    match (&run.outcome, expected) {
+        (Outcome::Solved(actual), Some(expected)) if actual == expected => Verdict::Pass,
+        (Outcome::Solved(actual), Some(expected)) => Verdict::Fail { expected, actual },
+        (Outcome::Solved(actual), None) => Verdict::Missing { actual },
+        (Outcome::NoInput, _) => Verdict::NoInput,
+        (outcome, _) => Verdict::Error(outcome),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Answers;
+    use crate::solver::{Day, Part};
+
+    #[test]
+    fn parse_answers() {
+        let answers: Answers = r#"
+[1]
+1 = "24000"
+2 = "45000"
+
+[10]
+2 = """
+##..
+..##"""
+"#
+        .parse()
+        .unwrap();
+        let day = |d| Day::new(d).unwrap();
+        assert_eq!(answers.get(day(1), Part::One), Some("24000"));
+        assert_eq!(answers.get(day(1), Part::Two), Some("45000"));
+        assert_eq!(answers.get(day(10), Part::One), None);
+        assert_eq!(answers.get(day(10), Part::Two), Some("##..\n..##"));
+    }
+
+    #[test]
+    fn reject_illegal_keys() {
+        assert!("[26]\n1 = \"1\"".parse::<Answers>().is_err());
+        assert!("[1]\n3 = \"1\"".parse::<Answers>().is_err());
+    }
+}
@@ -1,8 +1,11 @@
+use crate::generate::Rng;
+use crate::parse::number;
 use anyhow::Result;
 use std::iter::Sum;
 use std::ops::Add;
 use std::str::FromStr;
 
+#[derive(Copy, Clone)]
 enum Line<T> {
     Cal(T),
     NewLine,
@@ -15,24 +18,52 @@ impl FromStr for Line<usize> {
         if s.is_empty() {
             Ok(Line::NewLine)
         } else {
-            Ok(Line::Cal(s.parse()?))
+            Ok(Line::Cal(number(s).map_err(|e| {
+                e.hint(
+                    "each line holds the calories of one item, elves are separated by blank lines",
+                )
+            })?))
         }
     }
 }
 
-pub fn first(inp: &str) -> Result<String> {
+pub struct Calories(Vec<Line<usize>>);
+
+pub fn parse(inp: &str) -> Result<Calories> {
+    Ok(Calories(
+        inp.lines()
+            .map(|e| e.trim().parse::<Line<usize>>())
+            .collect::<Result<_>>()?,
+    ))
+}
+
+/// Elves carrying one to five items each.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    loop {
+        for _ in 0..rng.range(1..=5) {
+            out += &format!("{}\n", rng.range(1000..=60000));
+        }
+        if out.len() >= size {
+            return out;
+        }
+        out.push('\n');
+    }
+}
+
+pub fn first(inp: &Calories) -> Result<String> {
     find_n_most_cals::<1>(inp)
 }
 
-pub fn second(inp: &str) -> Result<String> {
+pub fn second(inp: &Calories) -> Result<String> {
     find_n_most_cals::<3>(inp)
 }
 
-pub fn find_n_most_cals<const N: usize>(inp: &str) -> Result<String> {
+pub fn find_n_most_cals<const N: usize>(inp: &Calories) -> Result<String> {
     Ok(inp
-        .lines()
-        .map(|e| e.trim().parse::<Line<usize>>().unwrap())
-        .fold(Agg::<N, usize>::new(), |agg, line| agg.and(line))
+        .0
+        .iter()
+        .fold(Agg::<N, usize>::new(), |agg, line| agg.and(*line))
         .result()
         .to_string())
 }
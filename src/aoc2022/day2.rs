@@ -1,6 +1,7 @@
 use crate::aoc2022::day2::Outcome::{Draw, Loss, Win};
-use crate::utils::utils::non_empty_lines;
-use anyhow::{anyhow, Result};
+use crate::generate::Rng;
+use crate::parse::{lines, split_once, ParseError};
+use anyhow::Result;
 use std::str::FromStr;
 
 #[derive(Debug)]
@@ -28,19 +29,68 @@ impl Outcome {
     }
 }
 
-impl FromStr for Outcome {
+impl From<Column> for Outcome {
+    fn from(c: Column) -> Self {
+        match c {
+            Column::X => Loss,
+            Column::Y => Draw,
+            Column::Z => Win,
+        }
+    }
+}
+
+impl From<Column> for Choice {
+    fn from(c: Column) -> Self {
+        match c {
+            Column::X => Choice::Rock,
+            Column::Y => Choice::Paper,
+            Column::Z => Choice::Scissors,
+        }
+    }
+}
+
+/// The second column of the strategy guide, its meaning differs between the parts.
+#[derive(Copy, Clone, Debug)]
+enum Column {
+    X,
+    Y,
+    Z,
+}
+
+impl FromStr for Column {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         match s {
-            "X" => Ok(Loss),
-            "Y" => Ok(Draw),
-            "Z" => Ok(Win),
-            _ => Err(anyhow!("invalid player B choice")),
+            "X" => Ok(Column::X),
+            "Y" => Ok(Column::Y),
+            "Z" => Ok(Column::Z),
+            _ => Err(ParseError::new(s, format!("invalid response '{s}'"))
+                .hint("expected X, Y or Z")
+                .into()),
         }
     }
 }
 
+struct Round {
+    elf: Choice,
+    col: Column,
+}
+
+impl FromStr for Round {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (a, b) = get_line(s)?;
+        Ok(Self {
+            elf: elf_play(a)?,
+            col: b.parse()?,
+        })
+    }
+}
+
+pub struct Rounds(Vec<Round>);
+
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 enum Choice {
     Rock,
@@ -69,7 +119,7 @@ impl Choice {
     fn opponent_play(&self, outcome: &Outcome) -> Self {
         use Choice::{Paper, Rock, Scissors};
         match (self, outcome) {
-            (a, Draw) => a.clone(),
+            (a, Draw) => *a,
             (Rock, Win) | (Paper, Loss) => Scissors,
             (Rock, Loss) | (Scissors, Win) => Paper,
             (Scissors, Loss) | (Paper, Win) => Rock,
@@ -99,20 +149,17 @@ impl Game for GameFirst {
     }
 }
 
-impl FromStr for GameOutcome {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let (a, b) = get_line(s)?;
-        let ca = elf_play(a)?;
-        let outcome = b.parse()?;
-        Ok(Self { elf: ca, outcome })
+impl From<&Round> for GameOutcome {
+    fn from(r: &Round) -> Self {
+        Self {
+            elf: r.elf,
+            outcome: r.col.into(),
+        }
     }
 }
 
 fn get_line(s: &str) -> Result<(&str, &str)> {
-    s.split_once(" ")
-        .ok_or_else(|| anyhow!("can't split game line: '{}'", s))
+    Ok(split_once(s, " ").map_err(|e| e.hint("rounds are written as `A Y`"))?)
 }
 
 fn elf_play(s: &str) -> Result<Choice> {
@@ -120,23 +167,18 @@ fn elf_play(s: &str) -> Result<Choice> {
         "A" => Ok(Choice::Rock),
         "B" => Ok(Choice::Paper),
         "C" => Ok(Choice::Scissors),
-        _ => Err(anyhow!("invalid player A choice")),
+        _ => Err(ParseError::new(s, format!("invalid opponent choice '{s}'"))
+            .hint("expected A, B or C")
+            .into()),
     }
 }
 
-impl FromStr for GameFirst {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let (a, b) = get_line(s)?;
-        let ca = elf_play(a)?;
-        let cb = match b {
-            "X" => Ok(Choice::Rock),
-            "Y" => Ok(Choice::Paper),
-            "Z" => Ok(Choice::Scissors),
-            _ => Err(anyhow!("invalid player B choice")),
-        }?;
-        Ok(GameFirst { a: ca, b: cb })
+impl From<&Round> for GameFirst {
+    fn from(r: &Round) -> Self {
+        Self {
+            a: r.elf,
+            b: r.col.into(),
+        }
     }
 }
 
@@ -144,17 +186,34 @@ pub trait Game {
     fn points(&self) -> usize;
 }
 
-pub fn second(inp: &str) -> Result<String> {
+pub fn parse(inp: &str) -> Result<Rounds> {
+    Ok(Rounds(lines(inp, |g| g.parse())?))
+}
+
+pub fn second(inp: &Rounds) -> Result<String> {
     run::<GameOutcome>(inp)
 }
 
-pub fn first(inp: &str) -> Result<String> {
+/// Rounds of random plays.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    loop {
+        out += &format!("{} {}\n", rng.char("ABC"), rng.char("XYZ"));
+        if out.len() >= size {
+            return out;
+        }
+    }
+}
+
+pub fn first(inp: &Rounds) -> Result<String> {
     run::<GameFirst>(inp)
 }
 
-fn run<G: Game + FromStr<Err = anyhow::Error>>(inp: &str) -> Result<String> {
-    Ok(non_empty_lines(inp)
-        .map(|g| G::from_str(g.trim()).unwrap())
+fn run<G: Game + for<'a> From<&'a Round>>(inp: &Rounds) -> Result<String> {
+    Ok(inp
+        .0
+        .iter()
+        .map(G::from)
         .fold(0, |sum, e| sum + e.points())
         .to_string())
 }
@@ -1,4 +1,5 @@
-use crate::utils::utils::non_empty_lines;
+use crate::generate::Rng;
+use crate::parse::{first_invalid, lines, ParseError};
 use anyhow::Result;
 use iter_tools::Itertools;
 
@@ -17,18 +18,68 @@ fn str_chars(s: &str) -> u64 {
         .fold(0, |sum, a| sum | a)
 }
 
-pub fn first(inp: &str) -> Result<String> {
-    let res: u32 = non_empty_lines(inp)
-        .map(|l| l.split_at(l.len() / 2))
-        .map(|p| [p.0, p.1].map(str_chars))
-        .map(|[a, b]| (a & b).trailing_zeros())
+struct Rucksack {
+    left: u64,
+    right: u64,
+}
+
+pub struct Rucksacks(Vec<Rucksack>);
+
+pub fn parse(inp: &str) -> Result<Rucksacks> {
+    Ok(Rucksacks(lines(inp, |l| {
+        if let Some(c) = first_invalid(l, |c| c.is_ascii_alphabetic()) {
+            return Err(ParseError::new(c, "items are letters")
+                .hint("each line lists the items of one rucksack")
+                .into());
+        }
+        if l.len() % 2 != 0 {
+            return Err(ParseError::new(l, "compartments differ in size")
+                .hint("both compartments of a rucksack hold the same number of items")
+                .into());
+        }
+        let (l, r) = l.split_at(l.len() / 2);
+        Ok(Rucksack {
+            left: str_chars(l),
+            right: str_chars(r),
+        })
+    })?))
+}
+
+const ITEMS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Groups of three rucksacks sharing a badge, each with an item in both compartments.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    loop {
+        let badge = rng.char(ITEMS);
+        for _ in 0..3 {
+            let half = rng.range(4..=16) as usize;
+            let mut items: Vec<_> = (0..2 * half).map(|_| rng.char(ITEMS)).collect();
+            items[half + rng.below(half)] = items[rng.below(half)];
+            items[rng.below(2 * half)] = badge;
+            out.extend(items);
+            out.push('\n');
+        }
+        if out.len() >= size {
+            return out;
+        }
+    }
+}
+
+pub fn first(inp: &Rucksacks) -> Result<String> {
+    let res: u32 = inp
+        .0
+        .iter()
+        .map(|r| (r.left & r.right).trailing_zeros())
         .sum();
     Ok(res.to_string())
 }
 
-pub fn second(inp: &str) -> Result<String> {
-    let res: u32 = non_empty_lines(inp)
-        .map(str_chars)
+pub fn second(inp: &Rucksacks) -> Result<String> {
+    let res: u32 = inp
+        .0
+        .iter()
+        .map(|r| r.left | r.right)
         .chunks(3)
         .into_iter()
         .flat_map(|g| g.reduce(|s, e| s & e))
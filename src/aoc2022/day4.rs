@@ -1,4 +1,5 @@
-use crate::utils::utils::non_empty_lines;
+use crate::generate::Rng;
+use crate::parse::{check_lines, lines, number, split_once, ParseError};
 use anyhow::Result;
 
 fn contains(a: &[u32; 2], b: &[u32; 2]) -> bool {
@@ -13,26 +14,65 @@ fn any_overlap(a: &[u32; 2], b: &[u32; 2]) -> bool {
     !(a[1] < b[0] || b[1] < a[0])
 }
 
-pub fn first(inp: &str) -> Result<String> {
-    parse_and_count(inp, contained)
+pub struct Assignments(Vec<[[u32; 2]; 2]>);
+
+fn range(s: &str) -> Result<[u32; 2]> {
+    let (l, u) = split_once(s, "-").map_err(|e| e.hint("ranges are written as `2-4`"))?;
+    Ok([number(l)?, number(u)?])
+}
+
+fn pair(l: &str) -> Result<(&str, &str)> {
+    Ok(split_once(l, ",").map_err(|e| e.hint("pairs are written as `2-4,6-8`"))?)
+}
+
+pub fn parse(inp: &str) -> Result<Assignments> {
+    Ok(Assignments(lines(inp, |l| {
+        let (a, b) = pair(l)?;
+        Ok([range(a)?, range(b)?])
+    })?))
+}
+
+/// Every line is a pair of ranges that don't end before they start.
+pub fn validate(inp: &str) -> Vec<ParseError> {
+    check_lines(inp, |l| {
+        let (a, b) = pair(l)?;
+        for r in [a, b] {
+            let [lower, upper] = range(r)?;
+            if lower > upper {
+                return Err(ParseError::new(r, "range ends before it starts").into());
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Pairs of section ranges within `1..=99`.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut range = || {
+        let lower = rng.range(1..=99);
+        format!("{lower}-{}", rng.range(lower..=99))
+    };
+    let mut out = String::new();
+    loop {
+        out += &format!("{},{}\n", range(), range());
+        if out.len() >= size {
+            return out;
+        }
+    }
+}
+
+pub fn first(inp: &Assignments) -> Result<String> {
+    count(inp, contained)
 }
 
-pub fn second(inp: &str) -> Result<String> {
-    parse_and_count(inp, any_overlap)
+pub fn second(inp: &Assignments) -> Result<String> {
+    count(inp, any_overlap)
 }
 
-fn parse_and_count<F>(inp: &str, mut pred: F) -> Result<String>
+fn count<F>(inp: &Assignments, mut pred: F) -> Result<String>
 where
     F: FnMut(&[u32; 2], &[u32; 2]) -> bool,
 {
-    let res = non_empty_lines(inp)
-        .map(|l| l.split_once(",").unwrap())
-        .map(|(a, b)| {
-            [a, b]
-                .map(|a| a.split_once("-").unwrap())
-                .map(|(l, u)| [l, u].map(|e| e.parse::<u32>().unwrap()))
-        })
-        .filter(|[f, s]| pred(f, s))
-        .count();
+    let res = inp.0.iter().filter(|[f, s]| pred(f, s)).count();
     Ok(res.to_string())
 }
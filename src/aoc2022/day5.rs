@@ -1,17 +1,22 @@
-use crate::utils::utils::non_empty_lines;
-use anyhow::{anyhow, Result};
+use crate::generate::Rng;
+use crate::parse::{check_lines, lines, number, split_once, ParseError};
+use anyhow::Result;
 use iter_tools::Itertools;
 use std::str::FromStr;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Stack(Vec<char>);
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Stacks(Vec<Stack>);
 
 impl Stacks {
     pub(crate) fn output(&self) -> String {
         self.0.iter().flat_map(|s| s.0.last().cloned()).join("")
     }
+
+    fn heights(&self) -> Vec<usize> {
+        self.0.iter().map(|s| s.0.len()).collect()
+    }
 }
 
 #[derive(Debug)]
@@ -21,15 +26,26 @@ struct Move {
     n: usize,
 }
 
+fn stack_index(s: &str) -> Result<usize> {
+    number::<usize>(s)?
+        .checked_sub(1)
+        .ok_or_else(|| ParseError::new(s, "stacks are numbered from 1").into())
+}
+
 impl FromStr for Move {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         let elms = s.split_whitespace().collect::<Vec<_>>();
+        let ["move", n, "from", from, "to", to] = elms[..] else {
+            return Err(ParseError::new(s, "illegal move")
+                .hint("moves are written as `move 1 from 2 to 3`")
+                .into());
+        };
         Ok(Move {
-            from: elms[3].parse::<usize>()? - 1,
-            to: elms[5].parse::<usize>()? - 1,
-            n: elms[1].parse::<usize>()?,
+            from: stack_index(from)?,
+            to: stack_index(to)?,
+            n: number(n)?,
         })
     }
 }
@@ -73,7 +89,9 @@ impl FromStr for Stacks {
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         let mut lines: Vec<_> = s.lines().collect();
-        let nums = lines.pop().ok_or_else(|| anyhow!("to few lines"))?;
+        let nums = lines
+            .pop()
+            .ok_or_else(|| ParseError::new(s, "expected a drawing of the stacks"))?;
         let mut stacks = Stacks(
             nums.split_whitespace()
                 .map(|_| Stack(Vec::new()))
@@ -96,21 +114,120 @@ impl FromStr for Stacks {
     }
 }
 
-fn run<C: Crane>(inp: &str) -> Result<String> {
-    let (stack_inp, act_inp) = inp
-        .split_once("\n\n")
-        .ok_or_else(|| anyhow!("illegal input"))?;
+pub struct Procedure {
+    stacks: Stacks,
+    moves: Vec<Move>,
+}
+
+pub fn parse(inp: &str) -> Result<Procedure> {
+    let (stack_inp, act_inp) = sections(inp)?;
     let stacks: Stacks = stack_inp.parse()?;
-    let moves: Vec<Move> = non_empty_lines(act_inp)
-        .map(|l| l.parse())
-        .collect::<Result<_>>()?;
-    Ok(C::execute_moves(stacks, &moves).output())
+    let mut heights = stacks.heights();
+    let moves = lines(act_inp, |l| parse_move(l, &mut heights))?;
+    Ok(Procedure { stacks, moves })
+}
+
+fn sections(inp: &str) -> Result<(&str, &str), ParseError> {
+    split_once(inp, "\n\n").map_err(|e| e.hint("a blank line separates the stacks from the moves"))
+}
+
+/// Parses a move, keeping track of the `heights` of the stacks so no move takes more
+/// crates than its stack holds.
+fn parse_move(l: &str, heights: &mut [usize]) -> Result<Move> {
+    let m: Move = l.parse()?;
+    let stacks = heights.len();
+    if m.from >= stacks || m.to >= stacks {
+        return Err(ParseError::new(l, format!("there are only {stacks} stacks")).into());
+    }
+    if m.n > heights[m.from] {
+        let n = l.split_whitespace().nth(1).unwrap_or(l);
+        return Err(ParseError::new(
+            n,
+            format!("stack {} only holds {} crates", m.from + 1, heights[m.from]),
+        )
+        .into());
+    }
+    heights[m.from] -= m.n;
+    heights[m.to] += m.n;
+    Ok(m)
+}
+
+/// The stacks are labelled `1` to `n` and every move is between two of them, taking at
+/// most the crates its stack holds.
+pub fn validate(inp: &str) -> Vec<ParseError> {
+    let (stack_inp, act_inp) = match sections(inp) {
+        Ok(sections) => sections,
+        Err(e) => return vec![e],
+    };
+    let Some(labels) = stack_inp.lines().last() else {
+        return vec![ParseError::new(
+            stack_inp,
+            "expected a drawing of the stacks",
+        )];
+    };
+    let labels: Vec<_> = labels.split_whitespace().collect();
+    let mut errs: Vec<_> = labels
+        .iter()
+        .enumerate()
+        .filter(|(i, label)| number::<usize>(label).ok() != Some(i + 1))
+        .map(|(i, label)| ParseError::new(label, format!("expected stack label {}", i + 1)))
+        .collect();
+    let mut heights = stack_inp
+        .parse::<Stacks>()
+        .map_or_else(|_| vec![0; labels.len()], |s| s.heights());
+    errs.extend(check_lines(act_inp, |l| {
+        parse_move(l, &mut heights)?;
+        Ok(())
+    }));
+    errs
+}
+
+fn run<C: Crane>(inp: &Procedure) -> Result<String> {
+    Ok(C::execute_moves(inp.stacks.clone(), &inp.moves).output())
+}
+
+/// Nine stacks of up to twenty crates, moved around without ever taking more crates than
+/// a stack holds.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut heights: Vec<_> = (0..9).map(|_| rng.range(1..=20) as usize).collect();
+    let top = heights.iter().copied().max().unwrap_or_default();
+    let mut out = String::new();
+    for level in (0..top).rev() {
+        let row = heights
+            .iter()
+            .map(|h| {
+                if level < *h {
+                    format!("[{}]", rng.char("ABCDEFGHIJKLMNOPQRSTUVWXYZ"))
+                } else {
+                    "   ".to_string()
+                }
+            })
+            .join(" ");
+        out += &format!("{row}\n");
+    }
+    out += &format!("{}\n\n", (1..=9).map(|i| format!(" {i} ")).join(" "));
+    loop {
+        let from = loop {
+            let from = rng.below(9);
+            if heights[from] > 0 {
+                break from;
+            }
+        };
+        let to = (from + 1 + rng.below(8)) % 9;
+        let n = rng.range(1..=heights[from].min(10) as u64) as usize;
+        heights[from] -= n;
+        heights[to] += n;
+        out += &format!("move {n} from {} to {}\n", from + 1, to + 1);
+        if out.len() >= size {
+            return out;
+        }
+    }
 }
 
-pub fn first(inp: &str) -> Result<String> {
+pub fn first(inp: &Procedure) -> Result<String> {
     run::<Crane9000>(inp)
 }
 
-pub fn second(inp: &str) -> Result<String> {
+pub fn second(inp: &Procedure) -> Result<String> {
     run::<Crane9001>(inp)
 }
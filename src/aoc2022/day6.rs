@@ -1,36 +1,60 @@
-use anyhow::{anyhow, Result};
+use crate::error::AocError;
+use crate::generate::Rng;
+use crate::parse::{first_invalid, ParseError};
+use anyhow::Result;
 use iter_tools::Itertools;
 
 fn done<const N: usize>(v: &[char; N]) -> bool {
-    return v.iter().unique().count() == N;
+    v.iter().unique().count() == N
 }
 
-fn run_first<const N: usize>(inp: &str) -> Result<usize> {
+fn run_first<const N: usize>(inp: &Signal) -> Result<usize> {
     let mut curr = [' '; N];
-    let mut chars = inp.chars();
-    for i in 0..N {
-        curr[i] = chars.next().unwrap();
+    let mut chars = inp.0.iter().copied();
+    for c in curr.iter_mut() {
+        *c = chars
+            .next()
+            .ok_or_else(|| AocError::NoSolution(format!("signal is shorter than {N}")))?;
     }
     if done(&curr) {
         return Ok(N);
     }
-    let mut i = 0;
-    for c in chars {
+    for (i, c) in chars.enumerate() {
         curr[i % N] = c;
         if done(&curr) {
             return Ok(N + i + 1);
         }
-        i += 1;
     }
-    Err(anyhow!("not found"))
+    Err(AocError::NoSolution(format!("no {N} distinct characters in a row")).into())
 }
 
-pub fn first(inp: &str) -> Result<String> {
+pub struct Signal(Vec<char>);
+
+pub fn parse(inp: &str) -> Result<Signal> {
+    let inp = inp.trim_end();
+    if let Some(c) = first_invalid(inp, |c| c.is_ascii_lowercase()) {
+        return Err(ParseError::new(c, "unexpected character")
+            .hint("the signal is a single line of lowercase letters")
+            .into());
+    }
+    Ok(Signal(inp.chars().collect()))
+}
+
+/// A signal of only three letters, so neither marker shows up before its very end.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out: String = (0..size.saturating_sub(15))
+        .map(|_| rng.char("abc"))
+        .collect();
+    out += "defghijklmnopq\n";
+    out
+}
+
+pub fn first(inp: &Signal) -> Result<String> {
     let res = run_first::<4>(inp)?;
     Ok(res.to_string())
 }
 
-pub fn second(inp: &str) -> Result<String> {
+pub fn second(inp: &Signal) -> Result<String> {
     let res = run_first::<14>(inp)?;
     Ok(res.to_string())
 }
@@ -1,9 +1,13 @@
+use crate::error::AocError;
+use crate::generate::Rng;
+use crate::parse::{number, split_once, ParseError};
 use anyhow::{anyhow, Result};
 use iter_tools::Itertools;
 use std::str::FromStr;
+use tracing::debug;
 
 #[derive(Debug)]
-struct FileSys {
+pub struct FileSys {
     nodes: Vec<Node>,
 }
 
@@ -43,7 +47,7 @@ enum INode {
     },
 }
 
-impl TryFrom<Commands> for FileSys {
+impl TryFrom<Commands<'_>> for FileSys {
     type Error = anyhow::Error;
 
     fn try_from(cmds: Commands) -> std::result::Result<Self, Self::Error> {
@@ -62,16 +66,17 @@ impl TryFrom<Commands> for FileSys {
                 },
             ),
             |(curr, mut fs), cmd| -> Result<(usize, FileSys)> {
+                let text = cmd.text();
                 match (cmd, fs.nodes.get(curr)) {
                     (
-                        Command::Cd { arg },
+                        Command::Cd { arg: ".." },
                         Some(Node {
                             parent: Some(par), ..
                         }),
-                    ) if arg == ".." => {
+                    ) => {
                         return Ok((*par, fs));
                     }
-                    (Command::Cd { arg }, _) if arg == "/" => {
+                    (Command::Cd { arg: "/" }, _) => {
                         return Ok((0, fs));
                     }
                     (
@@ -89,12 +94,14 @@ impl TryFrom<Commands> for FileSys {
                             [*children_pointer..(*children_pointer + *children_num)]
                             .iter()
                             .enumerate()
-                            .find(|(_, e)| e.name == arg)
-                            .ok_or_else(|| anyhow!("child not found"))?;
+                            .find(|(_, e)| e.is_dir() && e.name == arg)
+                            .ok_or_else(|| {
+                                ParseError::new(arg, format!("no directory '{arg}' here"))
+                            })?;
                         return Ok((dest + children_pointer, fs));
                     }
                     (
-                        Command::Ls { output },
+                        Command::Ls { output, .. },
                         Some(Node {
                             node: INode::Dir { .. },
                             ..
@@ -125,7 +132,7 @@ impl TryFrom<Commands> for FileSys {
                                 .collect(),
                         )
                     }
-                    _ => return Err(anyhow!("illegal command")),
+                    _ => return Err(ParseError::new(text, "illegal command").into()),
                 }
                 Ok((curr, fs))
             },
@@ -135,12 +142,22 @@ impl TryFrom<Commands> for FileSys {
 }
 
 #[derive(Debug, Default)]
-struct Commands(Vec<Command>);
+struct Commands<'a>(Vec<Command<'a>>);
 
+/// A command, keeping the input it was parsed from so errors can point at it.
 #[derive(Debug)]
-enum Command {
-    Cd { arg: String },
-    Ls { output: Vec<LsOutput> },
+enum Command<'a> {
+    Cd { arg: &'a str },
+    Ls { cmd: &'a str, output: Vec<LsOutput> },
+}
+
+impl<'a> Command<'a> {
+    fn text(&self) -> &'a str {
+        match self {
+            Command::Cd { arg } => arg,
+            Command::Ls { cmd, .. } => cmd,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -158,29 +175,31 @@ impl FromStr for FileSys {
             .try_fold(Commands::default(), |mut cmds, line| {
                 if let Some(cmd) = line.strip_prefix("$ ") {
                     if let Some(cd) = cmd.strip_prefix("cd ") {
-                        cmds.0.push(Command::Cd {
-                            arg: cd.to_string(),
-                        })
+                        cmds.0.push(Command::Cd { arg: cd })
                     } else if cmd.starts_with("ls") {
-                        cmds.0.push(Command::Ls { output: vec![] })
+                        cmds.0.push(Command::Ls {
+                            cmd,
+                            output: vec![],
+                        })
                     } else {
-                        return Err(anyhow!("illegal command: {}", line));
+                        return Err(ParseError::new(cmd, format!("unknown command '{cmd}'"))
+                            .hint("expected `cd <dir>` or `ls`"));
                     }
-                } else if let Some(Command::Ls { output }) = cmds.0.last_mut() {
+                } else if let Some(Command::Ls { output, .. }) = cmds.0.last_mut() {
                     if let Some(dir) = line.strip_prefix("dir ") {
                         output.push(LsOutput::Dir(dir.to_string()))
                     } else {
-                        let (s, name) = line
-                            .split_once(' ')
-                            .ok_or_else(|| anyhow!("illegal line: {}", line))?;
-                        let size: usize = s.parse()?;
+                        let (s, name) = split_once(line, " ")
+                            .map_err(|e| e.hint("files are listed as `<size> <name>`"))?;
+                        let size: usize = number(s)?;
                         output.push(LsOutput::File {
                             size,
                             name: name.to_string(),
                         })
                     }
                 } else {
-                    return Err(anyhow!("unexpected line: {}", line));
+                    return Err(ParseError::new(line, "output outside of `ls`")
+                        .hint("commands start with `$ `"));
                 }
                 Ok(cmds)
             })?;
@@ -188,35 +207,75 @@ impl FromStr for FileSys {
     }
 }
 
-pub fn first(inp: &str) -> Result<String> {
-    let file_sys: FileSys = inp.parse()?;
+pub fn parse(inp: &str) -> Result<FileSys> {
+    inp.parse()
+}
+
+/// How deep a generated directory tree gets, sizing recurses once per level.
+const MAX_DEPTH: usize = 100;
+
+/// A session exploring a deep directory tree, depth first.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    // the disk has to hold every file, so the more there are the smaller they get
+    let max_file = (400_000_000 / size.max(1)).clamp(1, 300_000) as u64;
+    let mut out = "$ cd /\n".to_string();
+    explore(rng, &mut out, size, max_file, 0);
+    out
+}
+
+fn explore(rng: &mut Rng, out: &mut String, size: usize, max_file: u64, depth: usize) {
+    *out += "$ ls\n";
+    let dirs = if depth < MAX_DEPTH && out.len() < size {
+        rng.range(1..=3)
+    } else {
+        0
+    };
+    for i in 0..dirs {
+        *out += &format!("dir d{i}\n");
+    }
+    for i in 0..rng.range(1..=3) {
+        *out += &format!("{} f{i}.txt\n", rng.range(1..=max_file));
+    }
+    for i in 0..dirs {
+        if out.len() >= size {
+            break;
+        }
+        *out += &format!("$ cd d{i}\n");
+        explore(rng, out, size, max_file, depth + 1);
+        *out += "$ cd ..\n";
+    }
+}
+
+pub fn first(file_sys: &FileSys) -> Result<String> {
     let res: usize = file_sys
         .nodes
         .iter()
         .filter(|n| n.is_dir())
-        .map(|n| n.size(&file_sys))
+        .map(|n| n.size(file_sys))
         .filter(|s| *s <= 100_000)
         .sum();
     Ok(res.to_string())
 }
 
-pub fn second(inp: &str) -> Result<String> {
-    let file_sys: FileSys = inp.parse()?;
+pub fn second(file_sys: &FileSys) -> Result<String> {
     let used_size = file_sys
         .nodes
         .first()
         .ok_or_else(|| anyhow!("no root node found"))?
-        .size(&file_sys);
-    let min_amount_to_delete = 30_000_000 - (70_000_000 - used_size);
-    dbg!(used_size, min_amount_to_delete);
+        .size(file_sys);
+    let free = 70_000_000_usize.checked_sub(used_size).ok_or_else(|| {
+        AocError::NoSolution(format!("{used_size} used does not fit on the disk"))
+    })?;
+    let min_amount_to_delete = 30_000_000_usize.saturating_sub(free);
+    debug!(used_size, min_amount_to_delete, "disk usage");
     let (_, res) = file_sys
         .nodes
         .iter()
         .filter(|n| n.is_dir())
-        .map(|n| (n.name.clone(), n.size(&file_sys)))
+        .map(|n| (n.name.clone(), n.size(file_sys)))
         .filter(|(_, s)| s >= &min_amount_to_delete)
         .sorted_by_key(|(_, s)| *s)
         .next()
-        .ok_or_else(|| anyhow!("no deletion candidate found"))?;
+        .ok_or_else(|| AocError::NoSolution("no deletion candidate found".into()))?;
     Ok(res.to_string())
 }
@@ -1,10 +1,13 @@
+use crate::error::AocError;
+use crate::generate::Rng;
+use crate::parse::{check_lines, first_invalid, ParseError};
 use anyhow::Result;
 use iter_tools::Itertools;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
-#[derive(Debug)]
-struct Grid<T> {
+#[derive(Debug, Clone)]
+pub struct Grid<T> {
     data: Vec<T>,
     rows: usize,
     cols: usize,
@@ -35,40 +38,58 @@ impl Display for Grid<u8> {
     }
 }
 
-impl FromStr for Grid<u8> {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let rows = s.lines().count();
-        let cols = s.len() / rows;
-        let data = s
-            .lines()
-            .flat_map(|i| i.bytes().map(|b| ((b - 48) | 0b10000000) + 1))
-            .collect();
-        Ok(Self { data, rows, cols })
+fn check_row(l: &str, cols: usize) -> Result<(), ParseError> {
+    if let Some(c) = first_invalid(l, |c| c.is_ascii_digit()) {
+        return Err(ParseError::new(c, "tree heights are digits"));
     }
+    if l.len() != cols {
+        return Err(ParseError::new(
+            l,
+            format!(
+                "expected {cols} trees like the first row, found {}",
+                l.len()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// The input is a rectangular grid of digits.
+pub fn validate(inp: &str) -> Vec<ParseError> {
+    let cols = inp.lines().next().map_or(0, str::len);
+    check_lines(inp, |l| Ok(check_row(l, cols)?))
 }
 
-impl FromStr for Grid<u32> {
+impl FromStr for Grid<u8> {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let rows = s.lines().count();
-        let cols = s.len() / rows;
-        let data = s
-            .lines()
-            .flat_map(|i| i.bytes().map(|b| (b - 48) as u32))
+        let lines: Vec<_> = s.lines().collect();
+        let cols = lines
+            .first()
+            .ok_or_else(|| ParseError::new(s, "expected a grid of tree heights"))?
+            .len();
+        for l in &lines {
+            check_row(l, cols)?;
+        }
+        let data = lines
+            .iter()
+            .flat_map(|i| i.bytes().map(|b| b - b'0'))
             .collect();
-        Ok(Self { data, rows, cols })
+        Ok(Self {
+            data,
+            rows: lines.len(),
+            cols,
+        })
     }
 }
 
 fn mark_forward(e: &mut [u8]) {
     let mut max = 0;
-    for i in 0..e.len() {
-        let curr = e[i] & 0b01111111;
+    for v in e.iter_mut() {
+        let curr = *v & 0b01111111;
         if curr > max {
-            e[i] = curr;
+            *v = curr;
             max = curr;
         }
     }
@@ -97,6 +118,15 @@ where
 }
 
 impl Grid<u8> {
+    /// Packs every height with a set "invisible" bit, offset by one so that 0 is below all trees.
+    fn encode(&self) -> Self {
+        Self {
+            data: self.data.iter().map(|b| (b | 0b10000000) + 1).collect(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+
     fn mark(mut self) -> Self {
         iter_flat_mut(&mut self.data, self.rows, self.cols, mark_forward);
         self.data.reverse();
@@ -174,14 +204,30 @@ impl From<Grid<u8>> for Grid<u32> {
     }
 }
 
-pub fn first(inp: &str) -> Result<String> {
-    let grid: Grid<u8> = inp.parse()?;
-    let marked_grid = grid.mark();
+pub fn parse(inp: &str) -> Result<Grid<u8>> {
+    inp.parse()
+}
+
+/// A square grid of random tree heights.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let n = ((size as f64).sqrt() as usize).max(3);
+    let mut out = String::with_capacity(n * (n + 1));
+    for _ in 0..n {
+        out.extend((0..n).map(|_| rng.char("0123456789")));
+        out.push('\n');
+    }
+    out
+}
+
+pub fn first(grid: &Grid<u8>) -> Result<String> {
+    let marked_grid = grid.encode().mark();
     Ok(marked_grid.marked().to_string())
 }
 
-pub fn second(inp: &str) -> Result<String> {
-    let grid = inp.parse::<Grid<u32>>()?;
-    let scored = grid.calc_scores();
-    Ok(scored.score().unwrap().to_string())
+pub fn second(grid: &Grid<u8>) -> Result<String> {
+    let scored = Grid::<u32>::from(grid.clone()).calc_scores();
+    let score = scored
+        .score()
+        .ok_or_else(|| AocError::NoSolution("the grid has no trees".to_string()))?;
+    Ok(score.to_string())
 }
@@ -1,9 +1,11 @@
-use anyhow::{anyhow, Result};
+use crate::generate::Rng;
+use crate::parse::{check_lines, lines, number, split_once, ParseError};
+use anyhow::Result;
 use iter_tools::Itertools;
 use std::collections::HashSet;
-use std::fmt::{Display, Formatter};
 use std::ops::{Add, Sub};
 use std::str::FromStr;
+use tracing::trace;
 
 #[derive(Copy, Clone)]
 enum Direction {
@@ -15,20 +17,26 @@ enum Direction {
 
 struct Move(Direction, u8);
 
+pub struct Moves(Vec<Move>);
+
 impl FromStr for Move {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let (d, n) = s.split_once(" ").ok_or_else(|| anyhow!("illegal line"))?;
+        let (d, n) = split_once(s, " ").map_err(|e| e.hint("moves are written as `R 4`"))?;
         Ok(Self(
             match d {
                 "U" => Direction::Up,
                 "D" => Direction::Down,
                 "R" => Direction::Right,
                 "L" => Direction::Left,
-                _ => return Err(anyhow!("illegal move")),
+                _ => {
+                    return Err(ParseError::new(d, format!("unknown direction '{d}'"))
+                        .hint("expected U, D, L or R")
+                        .into())
+                }
             },
-            n.parse()?,
+            number(n)?,
         ))
     }
 }
@@ -74,8 +82,6 @@ impl Location {
         if dy.abs() < 2 && dx.abs() < 2 {
             return self;
         }
-        let tx = truncate(dx);
-        let ty = truncate(dy);
         Self(self.0 + truncate(dx), self.1 + truncate(dy))
     }
 }
@@ -88,21 +94,21 @@ struct Ropes<const N: usize> {
 impl<const N: usize> Add<Direction> for Ropes<N> {
     type Output = Self;
 
-    fn add(mut self, rhs: Direction) -> Self::Output {
+    fn add(self, rhs: Direction) -> Self::Output {
         let Ropes {
             head,
             mut followers,
         } = self;
         let new_head = head + rhs;
         let mut leader = new_head;
-        for i in 0..followers.len() {
-            let new_loc = followers[i].follow(&leader);
+        for follower in followers.iter_mut() {
+            let new_loc = follower.follow(&leader);
             leader = new_loc;
-            followers[i] = new_loc;
+            *follower = new_loc;
         }
         Self {
             head: new_head,
-            followers: followers,
+            followers,
         }
     }
 }
@@ -122,7 +128,8 @@ impl<const N: usize> Ropes<N> {
             .expect("rope with 0 length not supported"))
     }
 
-    fn print(&self, min: i32, max: i32) {
+    /// Draws the knots within `min..max` in both directions, head first.
+    fn draw(&self, min: i32, max: i32) -> String {
         let Ropes { head, followers } = self;
         let mut lines = vec![];
         for y in min..max {
@@ -143,35 +150,61 @@ impl<const N: usize> Ropes<N> {
             lines.push(s);
         }
 
-        println!(
-            "{}\nH({},{}) - T({},{})\n",
+        format!(
+            "{}\nH({},{}) - T({},{})",
             lines.into_iter().rev().join("\n"),
             head.0,
             head.1,
             self.tail().0,
             self.tail().1
-        );
+        )
     }
 }
 
-pub fn run<const N: usize>(inp: &str) -> Result<String> {
+/// Every line is a direction out of `UDLR` followed by a positive number of steps.
+pub fn validate(inp: &str) -> Vec<ParseError> {
+    check_lines(inp, |l| {
+        let Move(_, n) = l.parse()?;
+        if n == 0 {
+            return Err(ParseError::new(l, "moves take at least one step").into());
+        }
+        Ok(())
+    })
+}
+
+pub fn parse(inp: &str) -> Result<Moves> {
+    Ok(Moves(lines(inp, |l| l.parse())?))
+}
+
+pub fn run<const N: usize>(inp: &Moves) -> Result<String> {
     let mut pos: HashSet<Location> = HashSet::new();
     let mut ropes = Ropes::<N>::new();
     pos.insert(ropes.tail());
-    for x in inp.lines() {
-        let Move(d, n) = x.parse()?;
-        for _ in 0..n {
-            ropes = ropes + d;
+    for Move(d, n) in &inp.0 {
+        for _ in 0..*n {
+            ropes = ropes + *d;
             pos.insert(ropes.tail());
         }
+        trace!("after {n} steps:\n{}", ropes.draw(-15, 15));
     }
     Ok(pos.len().to_string())
 }
 
-pub fn first(inp: &str) -> Result<String> {
+/// Random moves of up to twenty steps.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    loop {
+        out += &format!("{} {}\n", rng.char("UDLR"), rng.range(1..=20));
+        if out.len() >= size {
+            return out;
+        }
+    }
+}
+
+pub fn first(inp: &Moves) -> Result<String> {
     run::<1>(inp)
 }
 
-pub fn second(inp: &str) -> Result<String> {
+pub fn second(inp: &Moves) -> Result<String> {
     run::<9>(inp)
 }
@@ -0,0 +1,4 @@
+use crate::solver::solvers;
+
+solvers! { 2023;
+}
@@ -0,0 +1,601 @@
+use crate::runner::{run_day, Run, Selection};
+use crate::solver::{Parsed, Registry, Solver, Year};
+use crate::store::InputStore;
+use anyhow::{anyhow, Context, Result};
+use iter_tools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// Local, uncommitted, since timings only compare on the machine they were taken on.
+pub const BENCH_HISTORY_FILE: &str = "bench-history.toml";
+
+/// Committed, so every checkout and CI check against the same budgets.
+pub const BUDGETS_FILE: &str = "bench.toml";
+
+/// Changes smaller than this are timer noise and never count as a regression.
+const NOISE_MS: f64 = 0.01;
+
+/// How long a part may take in the run of [`check_day`] before it is left out.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The median time of parsing a day's input or of solving one of its parts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Timing {
+    pub year: u16,
+    pub day: u8,
+    /// `None` for the parse, which both parts of a day share.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part: Option<u8>,
+    pub median_ms: f64,
+}
+
+impl Timing {
+    /// `YYYY-D parse` or `YYYY-D-P`.
+    pub fn name(&self) -> String {
+        match self.part {
+            Some(part) => format!("{}-{}-{part}", self.year, self.day),
+            None => format!("{}-{} parse", self.year, self.day),
+        }
+    }
+
+    fn is_same(&self, other: &Timing) -> bool {
+        (self.year, self.day, self.part) == (other.year, other.day, other.part)
+    }
+}
+
+/// The timings of one `aoc bench --save` run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub label: String,
+    /// The `git describe` of the working tree, if it is a git checkout.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    /// Seconds since the unix epoch.
+    pub time: u64,
+    #[serde(default, rename = "timing")]
+    pub timings: Vec<Timing>,
+}
+
+impl Snapshot {
+    pub fn new(label: &str, timings: Vec<Timing>) -> Self {
+        Self {
+            label: label.to_string(),
+            revision: revision(),
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            timings,
+        }
+    }
+}
+
+fn revision() -> Option<String> {
+    let out = Command::new("git")
+        .args(["describe", "--always", "--dirty"])
+        .output()
+        .ok()?;
+    let rev = String::from_utf8(out.stdout).ok()?.trim().to_string();
+    (out.status.success() && !rev.is_empty()).then_some(rev)
+}
+
+/// The saved benchmark runs, stored in `bench-history.toml`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BenchHistory {
+    #[serde(default, rename = "snapshot")]
+    snapshots: Vec<Snapshot>,
+}
+
+impl BenchHistory {
+    pub fn load(pth: &Path) -> Result<Self> {
+        match fs::read_to_string(pth) {
+            Ok(s) => toml::from_str(&s)
+                .with_context(|| format!("illegal bench history file {}", pth.display())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, pth: &Path) -> Result<()> {
+        fs::write(pth, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, label: &str) -> Option<&Snapshot> {
+        self.snapshots.iter().find(|s| s.label == label)
+    }
+
+    /// Adds a snapshot, replacing an earlier one of the same label.
+    pub fn record(&mut self, snapshot: Snapshot) {
+        self.snapshots.retain(|s| s.label != snapshot.label);
+        self.snapshots.push(snapshot);
+    }
+}
+
+/// The agreed time budgets of each year, stored in `bench.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Budgets {
+    /// The most milliseconds the puzzles of a year may take in total, by year.
+    #[serde(default)]
+    budget_ms: BTreeMap<String, f64>,
+}
+
+impl Budgets {
+    /// Loads the budgets, an absent file means there are none.
+    pub fn load(pth: &Path) -> Result<Self> {
+        match fs::read_to_string(pth) {
+            Ok(s) => toml::from_str(&s)
+                .with_context(|| format!("illegal budgets file {}", pth.display())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn get(&self, year: Year) -> Option<f64> {
+        self.budget_ms.get(&year.to_string()).copied()
+    }
+}
+
+fn median(mut f: impl FnMut(), samples: usize) -> f64 {
+    let mut times: Vec<f64> = (0..samples.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed().as_secs_f64() * 1000.0
+        })
+        .collect();
+    times.sort_by(f64::total_cmp);
+    times[times.len() / 2]
+}
+
+/// A day that was run once before being timed, as a part that panics or runs away would
+/// take the whole benchmark run with it.
+pub struct CheckedDay {
+    pub runs: Vec<Run>,
+    /// The parsed input, if some part got past the parse so it is known to be sound.
+    pub parsed: Option<Parsed>,
+}
+
+impl CheckedDay {
+    /// The parts that produced an answer, the only ones worth timing.
+    pub fn passed(&self) -> Vec<&'static dyn Solver> {
+        self.runs
+            .iter()
+            .filter(|r| !r.outcome.is_failure())
+            .map(|r| r.solver)
+            .collect()
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &Run> {
+        self.runs.iter().filter(|r| r.outcome.is_failure())
+    }
+}
+
+/// Runs every part of a day once, see [`CheckedDay`].
+pub fn check_day(day: &[&'static dyn Solver], input: &str) -> CheckedDay {
+    let runs = run_day(day, input, Some(CHECK_TIMEOUT));
+    let parsed = runs
+        .iter()
+        .find(|r| !r.outcome.is_failure())
+        .map(|r| r.solver.parse(input).expect("the check run parsed it"));
+    CheckedDay { runs, parsed }
+}
+
+/// A part that could not be timed, as it failed or ran away in the [check](check_day).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure {
+    pub name: String,
+    pub outcome: String,
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.name, self.outcome)
+    }
+}
+
+/// The timings of a benchmark run, along with the parts that could not be timed.
+#[derive(Debug, Default)]
+pub struct BenchRun {
+    pub timings: Vec<Timing>,
+    pub failures: Vec<Failure>,
+    /// Year and day of the days without a personal input, which are not timed at all.
+    pub skipped: Vec<(u16, u8)>,
+}
+
+/// Times the parse and each part of a day `samples` times. Parts that fail in the
+/// [check](check_day) are recorded as failures, the parse is left out if no part is left.
+pub fn time_day(day: &[&'static dyn Solver], input: &str, samples: usize) -> BenchRun {
+    let checked = check_day(day, input);
+    let failures = checked
+        .failed()
+        .map(|run| {
+            let s = run.solver;
+            Failure {
+                name: format!("{}-{}-{}", s.year(), s.day(), s.part()),
+                outcome: run.outcome.to_string(),
+            }
+        })
+        .collect();
+    let passed = checked.passed();
+    let (Some(first), Some(parsed)) = (passed.first(), &checked.parsed) else {
+        return BenchRun {
+            failures,
+            ..BenchRun::default()
+        };
+    };
+    let timing = |part: Option<u8>, median_ms| Timing {
+        year: first.year().get(),
+        day: first.day().get(),
+        part,
+        median_ms,
+    };
+    let mut timings = vec![timing(None, median(|| drop(first.parse(input)), samples))];
+    for solver in passed {
+        let ms = median(|| drop(solver.solve_parsed(parsed)), samples);
+        timings.push(timing(Some(solver.part().get()), ms));
+    }
+    BenchRun {
+        timings,
+        failures,
+        skipped: vec![],
+    }
+}
+
+/// Times every selected day that has a personal input.
+pub fn time_all(
+    registry: &Registry,
+    sel: &Selection,
+    store: &InputStore,
+    samples: usize,
+) -> Result<BenchRun> {
+    let mut res = BenchRun::default();
+    let solvers: Vec<_> = registry
+        .year(sel.year)?
+        .filter(|s| sel.matches(*s))
+        .collect();
+    for day in solvers.chunk_by(|a, b| a.day() == b.day()) {
+        match store.read(sel.year, day[0].day(), false)? {
+            Some(inp) => {
+                let run = time_day(day, &inp, samples);
+                res.timings.extend(run.timings);
+                res.failures.extend(run.failures);
+            }
+            None => {
+                warn!("not timing {}-{}: no input", sel.year, day[0].day());
+                res.skipped.push((sel.year.get(), day[0].day().get()));
+            }
+        }
+    }
+    Ok(res)
+}
+
+pub fn total_ms(timings: &[Timing]) -> f64 {
+    // an empty `sum` of floats is -0.0
+    timings.iter().fold(0.0, |total, t| total + t.median_ms)
+}
+
+/// A timing next to the one it is compared to, either may be missing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub name: String,
+    pub before: Option<f64>,
+    pub after: Option<f64>,
+}
+
+impl Change {
+    /// How many times faster it got, below 1 if it got slower.
+    pub fn speedup(&self) -> Option<f64> {
+        Some(self.before? / self.after?)
+    }
+
+    /// Whether it got slower by more than `threshold` percent, ignoring noise, or is no
+    /// longer timed at all.
+    pub fn regressed(&self, threshold: f64) -> bool {
+        match (self.before, self.after) {
+            (Some(before), Some(after)) => {
+                after - before > NOISE_MS && after > before * (1.0 + threshold / 100.0)
+            }
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+/// Pairs up the timings of a run with those of `base`, followed by the ones of `base`
+/// that are missing now. Days the run skipped for lack of an input are left out.
+pub fn compare(base: &Snapshot, run: &BenchRun) -> Vec<Change> {
+    let timings = &run.timings;
+    let find =
+        |timings: &[Timing], t: &Timing| timings.iter().find(|o| o.is_same(t)).map(|o| o.median_ms);
+    let skipped = |t: &Timing| run.skipped.contains(&(t.year, t.day));
+    let current = timings.iter().map(|t| Change {
+        name: t.name(),
+        before: find(&base.timings, t),
+        after: Some(t.median_ms),
+    });
+    let missing = base
+        .timings
+        .iter()
+        .filter(|b| find(timings, b).is_none() && !skipped(b))
+        .map(|b| Change {
+            name: b.name(),
+            before: Some(b.median_ms),
+            after: None,
+        });
+    current.chain(missing).collect()
+}
+
+/// The total of a run must stay within a budget, which a part that did not finish
+/// exceeds.
+pub fn check_budget(run: &BenchRun, budget_ms: f64) -> Result<()> {
+    if !run.failures.is_empty() {
+        let names = run.failures.iter().map(|f| f.name.as_str()).join(", ");
+        return Err(anyhow!("{names} did not finish"));
+    }
+    let total = total_ms(&run.timings);
+    if total > budget_ms {
+        Err(anyhow!(
+            "took {total:.3}ms, over the budget of {budget_ms:.3}ms"
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+pub fn render_timings(timings: &[Timing]) -> String {
+    let names: Vec<_> = timings.iter().map(Timing::name).collect();
+    let width = names.iter().map(String::len).max().unwrap_or(0);
+    let mut res = String::new();
+    for (name, t) in names.iter().zip(timings) {
+        res += &format!("{name:<width$}  {:>10.3}ms\n", t.median_ms);
+    }
+    res + &format!("total: {:.3}ms\n", total_ms(timings))
+}
+
+/// A table of the changes, marking regressions of more than `threshold` percent.
+pub fn render_changes(base: &Snapshot, changes: &[Change], threshold: f64) -> String {
+    let rows: Vec<[String; 4]> = changes
+        .iter()
+        .map(|c| {
+            let change = match c.speedup() {
+                None if c.after.is_none() => "missing  REGRESSION".to_string(),
+                None => "new".to_string(),
+                Some(s) if c.regressed(threshold) => format!("{:.2}x slower  REGRESSION", 1.0 / s),
+                Some(s) if s >= 1.0 => format!("{s:.2}x faster"),
+                Some(s) => format!("{:.2}x slower", 1.0 / s),
+            };
+            let ms = |t: Option<f64>| t.map_or("-".to_string(), |t| format!("{t:.3}ms"));
+            [c.name.clone(), ms(c.before), ms(c.after), change]
+        })
+        .collect();
+    let base_name = match &base.revision {
+        Some(rev) => format!("{} ({rev})", base.label),
+        None => base.label.clone(),
+    };
+    let header = ["", &base_name, "now", "change"].map(str::to_string);
+    let widths = std::iter::once(&header)
+        .chain(&rows)
+        .fold([0; 4], |mut w, row| {
+            for (w, c) in w.iter_mut().zip(row) {
+                *w = (*w).max(c.len());
+            }
+            w
+        });
+    let mut res = String::new();
+    for [name, before, after, change] in std::iter::once(&header).chain(&rows) {
+        let line = format!(
+            "{name:<w0$}  {before:>w1$}  {after:>w2$}  {change}",
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+        );
+        res += line.trim_end();
+        res += "\n";
+    }
+    let before: f64 = changes.iter().filter_map(|c| c.before).sum();
+    let after: f64 = changes.iter().filter_map(|c| c.after).sum();
+    res + &format!("total: {after:.3}ms, was {before:.3}ms\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        check_budget, compare, time_all, time_day, BenchHistory, BenchRun, Budgets, Change,
+        Failure, Snapshot, Timing,
+    };
+    use crate::runner::Selection;
+    use crate::solver::{Day, Parsed, Part, Registry, Solver, Year};
+    use crate::store::InputStore;
+    use crate::test_dir::TestDir;
+    use anyhow::Result;
+    use std::fs;
+
+    /// Solves its first part and panics in its second.
+    struct Flaky(Part);
+
+    impl Solver for Flaky {
+        fn year(&self) -> Year {
+            Year::new(2022).unwrap()
+        }
+
+        fn day(&self) -> Day {
+            Day::new(1).unwrap()
+        }
+
+        fn part(&self) -> Part {
+            self.0
+        }
+
+        fn title(&self) -> &'static str {
+            "Flaky"
+        }
+
+        fn parse(&self, inp: &str) -> Result<Parsed> {
+            Ok(Box::new(inp.to_string()))
+        }
+
+        fn solve_parsed(&self, _: &Parsed) -> Result<String> {
+            match self.0 {
+                Part::One => Ok("1".to_string()),
+                Part::Two => panic!("boom"),
+            }
+        }
+    }
+
+    fn timing(day: u8, part: Option<u8>, median_ms: f64) -> Timing {
+        Timing {
+            year: 2022,
+            day,
+            part,
+            median_ms,
+        }
+    }
+
+    fn snapshot(label: &str, timings: Vec<Timing>) -> Snapshot {
+        Snapshot {
+            label: label.to_string(),
+            revision: None,
+            time: 0,
+            timings,
+        }
+    }
+
+    #[test]
+    fn flags_regressions_over_threshold() {
+        let base = snapshot("main", vec![timing(1, None, 1.0), timing(1, Some(1), 2.0)]);
+        let run = BenchRun {
+            timings: vec![
+                timing(1, None, 1.05),
+                timing(1, Some(1), 3.0),
+                timing(1, Some(2), 1.0),
+            ],
+            ..BenchRun::default()
+        };
+        let changes = compare(&base, &run);
+        let regressed: Vec<_> = changes.iter().map(|c| c.regressed(10.0)).collect();
+        assert_eq!(regressed, [false, true, false]);
+        assert_eq!(changes[1].speedup(), Some(2.0 / 3.0));
+        assert_eq!(changes[2].before, None);
+        let noise = Change {
+            name: "2022-1-1".to_string(),
+            before: Some(0.001),
+            after: Some(0.004),
+        };
+        assert!(!noise.regressed(10.0));
+    }
+
+    #[test]
+    fn missing_timings_are_regressions() {
+        let base = snapshot(
+            "main",
+            vec![
+                timing(1, None, 1.0),
+                timing(1, Some(2), 2.0),
+                timing(2, Some(1), 2.0),
+            ],
+        );
+        let run = BenchRun {
+            timings: vec![timing(1, None, 1.0)],
+            skipped: vec![(2022, 2)],
+            ..BenchRun::default()
+        };
+        let changes = compare(&base, &run);
+        // day 2 has no input, so its timings are not missing
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            changes[1],
+            Change {
+                name: "2022-1-2".to_string(),
+                before: Some(2.0),
+                after: None,
+            }
+        );
+        assert!(changes[1].regressed(10.0));
+    }
+
+    #[test]
+    fn budgets_cover_the_total() {
+        let mut run = BenchRun {
+            timings: vec![timing(1, None, 1.0), timing(1, Some(1), 2.5)],
+            ..BenchRun::default()
+        };
+        assert!(check_budget(&run, 3.5).is_ok());
+        assert_eq!(
+            check_budget(&run, 3.0).unwrap_err().to_string(),
+            "took 3.500ms, over the budget of 3.000ms"
+        );
+        run.failures.push(Failure {
+            name: "2022-1-2".to_string(),
+            outcome: "panicked: boom".to_string(),
+        });
+        assert_eq!(
+            check_budget(&run, 3.5).unwrap_err().to_string(),
+            "2022-1-2 did not finish"
+        );
+    }
+
+    #[test]
+    fn panicking_parts_are_failures() {
+        let run = time_day(&[&Flaky(Part::One), &Flaky(Part::Two)], "", 1);
+        let names: Vec<_> = run.timings.iter().map(Timing::name).collect();
+        assert_eq!(names, ["2022-1 parse", "2022-1-1"]);
+        assert_eq!(run.failures.len(), 1);
+        assert_eq!(run.failures[0].to_string(), "2022-1-2 panicked: boom");
+        let base = snapshot("main", vec![timing(1, Some(2), 1.0)]);
+        assert!(compare(&base, &run).iter().any(|c| c.regressed(10.0)));
+        assert!(check_budget(&run, 1000.0).is_err());
+    }
+
+    #[test]
+    fn budgets_are_per_year() {
+        let dir = TestDir::new("budgets");
+        let pth = dir.join("bench.toml");
+        assert_eq!(
+            Budgets::load(&pth).unwrap().get(Year::new(2022).unwrap()),
+            None
+        );
+        fs::write(&pth, "[budget_ms]\n2022 = 250.0\n").unwrap();
+        let budgets = Budgets::load(&pth).unwrap();
+        assert_eq!(budgets.get(Year::new(2022).unwrap()), Some(250.0));
+        assert_eq!(budgets.get(Year::new(2023).unwrap()), None);
+        fs::write(&pth, "budget = 1\n").unwrap();
+        assert!(Budgets::load(&pth).is_err());
+    }
+
+    #[test]
+    fn times_days_with_input() {
+        let dir = TestDir::new("bench");
+        fs::write(dir.join("2022-1.txt"), "1000\n2000\n\n3000\n").unwrap();
+        fs::write(dir.join("2022-2.txt"), "A Y\nnot a round\n").unwrap();
+        let sel: Selection = "2022-1..3".parse().unwrap();
+        let run = time_all(&Registry::new(), &sel, &InputStore::new(&*dir), 3).unwrap();
+        let names: Vec<_> = run.timings.iter().map(Timing::name).collect();
+        assert_eq!(names, ["2022-1 parse", "2022-1-1", "2022-1-2"]);
+        assert!(run.timings.iter().all(|t| t.median_ms >= 0.0));
+        let failed: Vec<_> = run.failures.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(failed, ["2022-2-1", "2022-2-2"]);
+        assert_eq!(run.skipped, [(2022, 3)]);
+    }
+
+    #[test]
+    fn saving_a_label_replaces_it() {
+        let mut history = BenchHistory::default();
+        history.record(snapshot("main", vec![timing(1, None, 1.0)]));
+        history.record(snapshot("other", vec![]));
+        history.record(snapshot("main", vec![timing(1, Some(1), 2.0)]));
+        let history: BenchHistory = toml::from_str(&toml::to_string(&history).unwrap()).unwrap();
+        assert_eq!(history.snapshots.len(), 2);
+        assert_eq!(
+            history.get("main").unwrap().timings,
+            [timing(1, Some(1), 2.0)]
+        );
+    }
+}
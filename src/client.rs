@@ -0,0 +1,136 @@
+use crate::input::{encrypted_path, input_path};
+use crate::solver::{Day, Part, Year};
+use crate::submit::Reply;
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use ureq::Agent;
+
+pub const USER_AGENT: &str = concat!("github.com/SimonSchneider/aoc v", env!("CARGO_PKG_VERSION"));
+
+/// A client for the Advent of Code site, authenticated with a session token.
+pub struct Client {
+    agent: Agent,
+    base_url: String,
+    session: String,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        let agent = Agent::config_builder()
+            .user_agent(USER_AGENT)
+            .http_status_as_error(false)
+            .timeout_global(Some(Duration::from_secs(30)))
+            .build()
+            .into();
+        Self {
+            agent,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+        }
+    }
+
+    pub fn input(&self, year: Year, day: Day) -> Result<String> {
+        let mut res = self
+            .agent
+            .get(format!("{}/{year}/day/{day}/input", self.base_url))
+            .header("Cookie", format!("session={}", self.session))
+            .call()?;
+        let body = res.body_mut().read_to_string()?;
+        match res.status().as_u16() {
+            200 => Ok(body),
+            400 => Err(anyhow!("session token was rejected: {}", body.trim())),
+            404 => Err(anyhow!("puzzle {year}-{day} is not available yet")),
+            status => Err(anyhow!("fetching {year}-{day} failed with {status}")),
+        }
+    }
+
+    pub fn submit(
+        &self,
+        year: Year,
+        day: Day,
+        part: Part,
+        answer: &str,
+    ) -> Result<(Reply, Option<Duration>)> {
+        let mut res = self
+            .agent
+            .post(format!("{}/{year}/day/{day}/answer", self.base_url))
+            .header("Cookie", format!("session={}", self.session))
+            .send_form([("level", part.to_string().as_str()), ("answer", answer)])?;
+        let body = res.body_mut().read_to_string()?;
+        match res.status().as_u16() {
+            200 => Reply::parse(&body),
+            400 => Err(anyhow!("session token was rejected: {}", body.trim())),
+            status => Err(anyhow!(
+                "submitting {year}-{day}-{part} failed with {status}"
+            )),
+        }
+    }
+}
+
+/// Downloads the input of a day into `dir` unless it is already there, returning its
+/// path and whether it was downloaded.
+pub fn fetch_input(client: &Client, dir: &Path, year: Year, day: Day) -> Result<(PathBuf, bool)> {
+    let pth = input_path(dir, year, day, false);
+    if pth.exists() || encrypted_path(&pth).exists() {
+        return Ok((pth, false));
+    }
+    let inp = client.input(year, day)?;
+    fs::create_dir_all(dir)?;
+    fs::write(&pth, inp)?;
+    Ok((pth, true))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{fetch_input, Client, USER_AGENT};
+    use crate::fake_aoc::FakeAoc;
+    use crate::solver::{Day, Year};
+    use crate::test_dir::TestDir;
+    use std::fs;
+
+    fn ymd(year: u16, day: u8) -> (Year, Day) {
+        (Year::new(year).unwrap(), Day::new(day).unwrap())
+    }
+
+    #[test]
+    fn fetch_and_cache_input() {
+        let server = FakeAoc::start("secret");
+        let dir = TestDir::new("fetch");
+        let client = Client::new(&server.url(), "secret");
+        let (year, day) = ymd(2022, 10);
+
+        let (pth, fetched) = fetch_input(&client, &dir, year, day).unwrap();
+        assert!(fetched);
+        assert_eq!(pth, dir.join("2022-10.txt"));
+        assert_eq!(fs::read_to_string(&pth).unwrap(), "input for 2022-10\n");
+
+        let (_, fetched) = fetch_input(&client, &dir, year, day).unwrap();
+        assert!(!fetched);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/2022/day/10/input");
+        assert_eq!(requests[0].header("user-agent"), Some(USER_AGENT));
+        assert_eq!(requests[0].header("cookie"), Some("session=secret"));
+    }
+
+    #[test]
+    fn rejected_session() {
+        let server = FakeAoc::start("secret");
+        let client = Client::new(&server.url(), "wrong");
+        let (year, day) = ymd(2022, 1);
+        let err = fetch_input(&client, &TestDir::new("fetch"), year, day).unwrap_err();
+        assert!(err.to_string().starts_with("session token was rejected"));
+    }
+
+    #[test]
+    fn unreleased_puzzle() {
+        let server = FakeAoc::start("secret");
+        let client = Client::new(&server.url(), "secret");
+        let (year, day) = ymd(2099, 1);
+        let err = client.input(year, day).unwrap_err();
+        assert_eq!(err.to_string(), "puzzle 2099-1 is not available yet");
+    }
+}
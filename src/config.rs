@@ -0,0 +1,126 @@
+use crate::input::INPUTS_DIR;
+use crate::store::InputStore;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Local, uncommitted settings, since they contain the personal session token.
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// The checkout the binary was built from, holding the sources, answers and histories, so
+/// running from one of its subdirectories still uses the same files.
+pub const ROOT_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+/// A path relative to [`ROOT_DIR`].
+pub fn root_path(rel: impl AsRef<Path>) -> PathBuf {
+    Path::new(ROOT_DIR).join(rel)
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Value of the `session` cookie of a logged in adventofcode.com browser session.
+    pub session: Option<String>,
+    pub base_url: Option<String>,
+    /// Directory of the puzzle inputs, relative to the config file.
+    pub inputs_dir: Option<PathBuf>,
+    #[serde(skip)]
+    dir: PathBuf,
+}
+
+impl Config {
+    /// Loads the config file named by `AOC_CONFIG`, or the first `aoc.toml` found in the
+    /// current directory or one of its parents.
+    pub fn discover() -> Result<Self> {
+        if let Some(pth) = env::var_os("AOC_CONFIG") {
+            return Self::load(Path::new(&pth));
+        }
+        let cwd = env::current_dir()?;
+        match cwd
+            .ancestors()
+            .map(|d| d.join(CONFIG_FILE))
+            .find(|p| p.is_file())
+        {
+            Some(pth) => Self::load(&pth),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Loads the config file, an absent file means the default config.
+    pub fn load(pth: &Path) -> Result<Self> {
+        let mut config: Self = match fs::read_to_string(pth) {
+            Ok(s) => toml::from_str(&s)
+                .with_context(|| format!("illegal config file {}", pth.display()))?,
+            Err(e) if e.kind() == ErrorKind::NotFound => Self::default(),
+            Err(e) => return Err(e.into()),
+        };
+        config.dir = pth.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(config)
+    }
+
+    /// The session token from `AOC_SESSION` or the config file.
+    pub fn session(&self) -> Result<String> {
+        env::var("AOC_SESSION")
+            .ok()
+            .or_else(|| self.session.clone())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| {
+                anyhow!("no session token, set AOC_SESSION or `session` in {CONFIG_FILE}")
+            })
+    }
+
+    /// The site to talk to from `AOC_BASE_URL` or the config file.
+    pub fn base_url(&self) -> String {
+        env::var("AOC_BASE_URL")
+            .ok()
+            .or_else(|| self.base_url.clone())
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+    }
+
+    /// The inputs directory from `AOC_INPUTS` or the config file, `inputs` in [`ROOT_DIR`]
+    /// by default.
+    pub fn inputs_dir(&self) -> PathBuf {
+        env::var_os("AOC_INPUTS")
+            .map(PathBuf::from)
+            .or_else(|| self.inputs_dir.as_ref().map(|d| self.dir.join(d)))
+            .unwrap_or_else(|| root_path(INPUTS_DIR))
+    }
+
+    pub fn store(&self) -> InputStore {
+        InputStore::new(self.inputs_dir())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Config;
+    use crate::test_dir::TestDir;
+    use std::fs;
+
+    #[test]
+    fn load_config() {
+        let dir = TestDir::new("config");
+        let pth = dir.join("aoc.toml");
+
+        fs::write(&pth, "inputs_dir = \"../shared/inputs\"\n").unwrap();
+        let config = Config::load(&pth).unwrap();
+        assert_eq!(
+            config.dir.join(config.inputs_dir.unwrap()),
+            dir.join("../shared/inputs")
+        );
+
+        assert!(Config::load(&dir.join("missing.toml"))
+            .unwrap()
+            .inputs_dir
+            .is_none());
+
+        fs::write(&pth, "inputs = \"typo\"\n").unwrap();
+        assert!(Config::load(&pth).is_err());
+    }
+}
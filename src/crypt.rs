@@ -0,0 +1,127 @@
+use anyhow::{anyhow, Context, Result};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Local, uncommitted file holding the secret inputs are encrypted with.
+pub const KEY_FILE: &str = "aoc.key";
+
+/// Extension appended to the name of an encrypted input, e.g. `2022-7.txt.enc`.
+pub const ENCRYPTED_EXT: &str = "enc";
+
+const NONCE_LEN: usize = 12;
+
+const KEY_LEN: usize = 32;
+
+/// The key personal inputs are encrypted with, so they can be committed without being
+/// published. It is used as is rather than derived from a passphrase, so it has to be
+/// random, e.g. from `openssl rand -hex 32`.
+pub struct Key(chacha20poly1305::Key);
+
+impl Key {
+    /// The key written as 64 hex digits, anything shorter or longer is rejected.
+    pub fn new(secret: &str) -> Result<Self> {
+        let hex = secret.trim();
+        if hex.len() != 2 * KEY_LEN || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(anyhow!(
+                "the key must be {KEY_LEN} random bytes written as {} hex digits, \
+                 e.g. from `openssl rand -hex {KEY_LEN}`",
+                2 * KEY_LEN
+            ));
+        }
+        let bytes: Vec<_> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("checked above"))
+            .collect();
+        Ok(Self(*chacha20poly1305::Key::from_slice(&bytes)))
+    }
+
+    /// The key from the secret in `AOC_KEY`, the file named by `AOC_KEY_FILE`, or the
+    /// first `aoc.key` found in the current directory or one of its parents.
+    pub fn discover() -> Result<Option<Self>> {
+        if let Ok(secret) = env::var("AOC_KEY") {
+            return Self::new(&secret)
+                .context("illegal key in AOC_KEY")
+                .map(Some);
+        }
+        let pth = match env::var_os("AOC_KEY_FILE") {
+            Some(pth) => Some(Path::new(&pth).to_path_buf()),
+            None => env::current_dir()?
+                .ancestors()
+                .map(|d| d.join(KEY_FILE))
+                .find(|p| p.is_file()),
+        };
+        match pth {
+            Some(pth) => {
+                let secret = fs::read_to_string(&pth)
+                    .with_context(|| format!("can't read key file {}", pth.display()))?;
+                let key = Self::new(&secret)
+                    .with_context(|| format!("illegal key in {}", pth.display()))?;
+                Ok(Some(key))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Like [`Key::discover`], but failing with a hint on where to put the key.
+    pub fn require() -> Result<Self> {
+        Self::discover()?.ok_or_else(|| {
+            anyhow!("no key for encrypted inputs, set AOC_KEY or AOC_KEY_FILE or create {KEY_FILE}")
+        })
+    }
+
+    /// Encrypts with a random nonce, which is stored in front of the ciphertext.
+    pub fn encrypt(&self, plain: &[u8]) -> Result<Vec<u8>> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let encrypted = ChaCha20Poly1305::new(&self.0)
+            .encrypt(&nonce, plain)
+            .map_err(|_| anyhow!("encryption failed"))?;
+        Ok([nonce.as_slice(), &encrypted].concat())
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if data.len() < NONCE_LEN {
+            return Err(anyhow!("encrypted data is truncated"));
+        }
+        let (nonce, encrypted) = data.split_at(NONCE_LEN);
+        ChaCha20Poly1305::new(&self.0)
+            .decrypt(Nonce::from_slice(nonce), encrypted)
+            .map_err(|_| anyhow!("can't decrypt, the key is wrong or the data is damaged"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Key;
+
+    const SECRET: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    #[test]
+    fn roundtrip() {
+        let key = Key::new(&format!("{SECRET}\n")).unwrap();
+        let encrypted = key.encrypt(b"1000\n2000\n").unwrap();
+        assert_ne!(&encrypted[12..], b"1000\n2000\n");
+        assert_eq!(
+            Key::new(SECRET).unwrap().decrypt(&encrypted).unwrap(),
+            b"1000\n2000\n"
+        );
+        let other = SECRET.replace("1f", "ff");
+        assert!(Key::new(&other).unwrap().decrypt(&encrypted).is_err());
+        assert!(key.decrypt(&encrypted[..8]).is_err());
+    }
+
+    #[test]
+    fn keys_are_32_bytes_of_hex() {
+        for secret in [
+            "secret",
+            &SECRET[..62],
+            &format!("{SECRET}00"),
+            &SECRET.replace("0a", "+a"),
+        ] {
+            assert!(Key::new(secret).is_err(), "{secret}");
+        }
+        assert!(Key::new(&SECRET.to_uppercase()).is_ok());
+    }
+}
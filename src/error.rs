@@ -0,0 +1,92 @@
+use crate::solver::{Day, Part, Year};
+use iter_tools::Itertools;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+/// The failures callers may want to tell apart, they convert into and can be downcast
+/// from an [`anyhow::Error`].
+#[derive(Debug)]
+pub enum AocError {
+    UnknownYear {
+        year: Year,
+        available: Vec<Year>,
+    },
+    UnknownPuzzle {
+        year: Year,
+        day: Day,
+        part: Part,
+    },
+    MissingInput {
+        year: Year,
+        day: Day,
+        path: Option<PathBuf>,
+    },
+    Parse(anyhow::Error),
+    /// The solver ran to completion without finding an answer.
+    NoSolution(String),
+}
+
+impl AocError {
+    /// The process exit code the CLI reports this error with.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            AocError::UnknownYear { .. } | AocError::UnknownPuzzle { .. } => 3,
+            AocError::MissingInput { .. } => 4,
+            AocError::Parse(_) => 5,
+            AocError::NoSolution(_) => 6,
+        }
+    }
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocError::UnknownYear { year, available } => write!(
+                f,
+                "unknown year {year}, available years are: {}",
+                available.iter().join(", ")
+            ),
+            AocError::UnknownPuzzle { year, day, part } => {
+                write!(f, "puzzle {year}-{day}-{part} is not implemented")
+            }
+            AocError::MissingInput {
+                year,
+                day,
+                path: Some(path),
+            } => write!(
+                f,
+                "no input for {year}-{day}, {} is missing",
+                path.display()
+            ),
+            AocError::MissingInput { year, day, .. } => write!(f, "no input for {year}-{day}"),
+            AocError::Parse(e) => write!(f, "failed to parse input: {e:#}"),
+            AocError::NoSolution(msg) => write!(f, "no solution found: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for AocError {}
+
+#[cfg(test)]
+mod test {
+    use super::AocError;
+    use crate::solver::{Day, Part, Registry, Year};
+
+    #[test]
+    fn errors_survive_anyhow() {
+        let registry = Registry::new();
+        let (year, day) = (Year::new(2022).unwrap(), Day::new(9).unwrap());
+        let err = registry
+            .get(year, day, Part::One)
+            .unwrap()
+            .solve("jump 3")
+            .unwrap_err();
+        let err = err.downcast_ref::<AocError>().unwrap();
+        assert!(matches!(err, AocError::Parse(_)));
+        assert_eq!(err.exit_code(), 5);
+        assert_eq!(
+            err.to_string(),
+            "failed to parse input: line 1, column 1: unknown direction 'jump'"
+        );
+    }
+}
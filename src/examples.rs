@@ -0,0 +1,15 @@
+use crate::solver::{Day, Registry, Year};
+
+fn check(year: u16, day: u8, part: &str, inp: &str, expected: &str) {
+    let registry = Registry::new();
+    let solver = registry
+        .get(
+            Year::new(year).unwrap(),
+            Day::new(day).unwrap(),
+            part.parse().unwrap(),
+        )
+        .unwrap();
+    assert_eq!(solver.solve(inp).unwrap(), expected.trim_end());
+}
+
+include!(concat!(env!("OUT_DIR"), "/examples.rs"));
@@ -0,0 +1,173 @@
+//! A local stand-in for the adventofcode.com endpoints used by the client tests.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+#[derive(Default)]
+struct State {
+    requests: Vec<Request>,
+    /// Correct answers by answer path and level, and whether they were submitted.
+    answers: HashMap<(String, String), (String, bool)>,
+    rate_limit: Option<Duration>,
+}
+
+pub struct FakeAoc {
+    port: u16,
+    state: Arc<Mutex<State>>,
+}
+
+impl FakeAoc {
+    /// Serves on a random local port, accepting only the given session token.
+    pub fn start(session: &str) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let state = Arc::new(Mutex::new(State::default()));
+        let (session, shared) = (session.to_string(), state.clone());
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if let Some(req) = read_request(&stream) {
+                    let mut state = shared.lock().unwrap();
+                    let (status, body) = respond(&session, &mut state, &req);
+                    state.requests.push(req);
+                    drop(state);
+                    write_response(stream, status, &body);
+                }
+            }
+        });
+        Self { port, state }
+    }
+
+    pub fn set_answer(&self, path: &str, level: u8, answer: &str) {
+        self.state.lock().unwrap().answers.insert(
+            (path.to_string(), level.to_string()),
+            (answer.to_string(), false),
+        );
+    }
+
+    /// Makes the next answer submission fail with the given wait.
+    pub fn rate_limit(&self, wait: Duration) {
+        self.state.lock().unwrap().rate_limit = Some(wait);
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+fn respond(session: &str, state: &mut State, req: &Request) -> (u16, String) {
+    if req.header("cookie") != Some(&format!("session={session}")) {
+        return (
+            400,
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n".into(),
+        );
+    }
+    let parts: Vec<_> = req.path.trim_start_matches('/').split('/').collect();
+    match (req.method.as_str(), &parts[..]) {
+        ("GET", [year, "day", day, "input"]) if year.parse::<u16>().unwrap_or(0) < 2099 => {
+            (200, format!("input for {year}-{day}\n"))
+        }
+        ("POST", [_, "day", _, "answer"]) => (200, article(answer(state, req))),
+        _ => (404, "404 Not Found\n".into()),
+    }
+}
+
+fn answer(state: &mut State, req: &Request) -> String {
+    if let Some(wait) = state.rate_limit.take() {
+        return format!(
+            "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have {}s left to wait.",
+            wait.as_secs()
+        );
+    }
+    let form: HashMap<_, _> = req
+        .body
+        .split('&')
+        .filter_map(|kv| kv.split_once('='))
+        .collect();
+    let key = (
+        req.path.clone(),
+        form.get("level").unwrap_or(&"").to_string(),
+    );
+    let given = form.get("answer").unwrap_or(&"");
+    match state.answers.get_mut(&key) {
+        Some((_, true)) | None => {
+            "You don't seem to be solving the right level.  Did you already complete it?".into()
+        }
+        Some((correct, solved)) if correct == given => {
+            *solved = true;
+            "That's the right answer!  You are one gold star closer to saving your vacation.".into()
+        }
+        Some((correct, _)) => {
+            let hint = match (given.parse::<i64>(), correct.parse::<i64>()) {
+                (Ok(g), Ok(c)) if g > c => "; your answer is too high",
+                (Ok(g), Ok(c)) if g < c => "; your answer is too low",
+                _ => "",
+            };
+            format!(
+                "That's not the right answer{hint}.  Please wait one minute before trying again."
+            )
+        }
+    }
+}
+
+fn article(text: String) -> String {
+    format!("<html><body><main><article><p>{text}</p></article></main></body></html>")
+}
+
+fn read_request(stream: &TcpStream) -> Option<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut request_line = line.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let mut headers = HashMap::new();
+    loop {
+        line.clear();
+        reader.read_line(&mut line).ok()?;
+        match line.trim_end().split_once(": ") {
+            Some((k, v)) => headers.insert(k.to_lowercase(), v.to_string()),
+            None => break,
+        };
+    }
+    let len = headers
+        .get("content-length")
+        .map_or(0, |l: &String| l.parse().unwrap_or(0));
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body).ok()?;
+    Some(Request {
+        method,
+        path,
+        headers,
+        body: String::from_utf8(body).ok()?,
+    })
+}
+
+fn write_response(mut stream: TcpStream, status: u16, body: &str) {
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {status} X\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+}
@@ -0,0 +1,117 @@
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+/// Produces a valid input of roughly `size` bytes for a day, see [`Solver::generate`].
+///
+/// [`Solver::generate`]: crate::solver::Solver::generate
+pub type Generator = fn(&mut Rng, usize) -> String;
+
+/// A small seeded pseudo random generator (SplitMix64). Kept in the crate so a seed gives
+/// the same input on every machine and across dependency updates, which keeps benchmark
+/// results comparable.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number within `range`, which must not be empty.
+    pub fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (lo, hi) = range.into_inner();
+        match (hi - lo).checked_add(1) {
+            Some(n) => lo + self.next_u64() % n,
+            None => self.next_u64(),
+        }
+    }
+
+    /// An index below `n`, which must not be 0.
+    pub fn below(&mut self, n: usize) -> usize {
+        self.range(0..=n as u64 - 1) as usize
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    /// A random character of `chars`, which must be ASCII.
+    pub fn char(&mut self, chars: &str) -> char {
+        *self.pick(chars.as_bytes()) as char
+    }
+}
+
+/// Estimates how the time taken grows with the input size, as the exponent `k` of the
+/// `size^k` best fitting the samples on a log-log scale. `None` unless there are samples
+/// for at least two sizes.
+pub fn growth(samples: &[(usize, Duration)]) -> Option<f64> {
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .filter(|(size, time)| *size > 0 && !time.is_zero())
+        .map(|(size, time)| ((*size as f64).ln(), time.as_secs_f64().ln()))
+        .collect();
+    if points.len() < 2 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let var_x: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    if var_x == 0.0 {
+        return None;
+    }
+    let cov: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    Some(cov / var_x)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{growth, Rng};
+    use crate::solver::Registry;
+    use std::time::Duration;
+
+    #[test]
+    fn seeds_are_reproducible() {
+        let draw = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..8).map(|_| rng.range(1..=6)).collect::<Vec<_>>()
+        };
+        assert_eq!(draw(7), draw(7));
+        assert_ne!(draw(7), draw(8));
+        assert!(draw(7).iter().all(|n| (1..=6).contains(n)));
+    }
+
+    #[test]
+    fn estimates_growth() {
+        let quadratic: Vec<_> = [10, 100, 1000]
+            .map(|n| (n, Duration::from_nanos(n as u64 * n as u64)))
+            .into();
+        assert!((growth(&quadratic).unwrap() - 2.0).abs() < 1e-9);
+        assert_eq!(growth(&quadratic[..1]), None);
+    }
+
+    #[test]
+    fn generated_inputs_are_solved() {
+        let registry = Registry::new();
+        for solver in registry.solvers() {
+            for size in [1, 5_000] {
+                let Some(inp) = solver.generate(1, size) else {
+                    continue;
+                };
+                let prob = format!("{}-{}-{}", solver.year(), solver.day(), solver.part());
+                assert_eq!(solver.validate(&inp).unwrap_or_default(), vec![], "{prob}");
+                if let Err(e) = solver.solve(&inp) {
+                    panic!("{prob} failed on a generated input of {size} bytes: {e:#}");
+                }
+            }
+        }
+    }
+}
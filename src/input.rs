@@ -0,0 +1,139 @@
+use crate::crypt::{Key, ENCRYPTED_EXT};
+use crate::solver::{Day, Year};
+use crate::store::InputStore;
+use anyhow::{anyhow, Context, Result};
+use std::borrow::Cow;
+use std::fs;
+use std::io::ErrorKind;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+pub const INPUTS_DIR: &str = "inputs";
+
+pub fn input_path(dir: &Path, year: Year, day: Day, test: bool) -> PathBuf {
+    let t_str = if test { "-test" } else { "" };
+    dir.join(format!("{year}-{day}{t_str}.txt"))
+}
+
+/// The encrypted counterpart of an input file, `2022-7.txt.enc` for `2022-7.txt`.
+pub fn encrypted_path(pth: &Path) -> PathBuf {
+    let mut s = pth.as_os_str().to_owned();
+    s.push(format!(".{ENCRYPTED_EXT}"));
+    PathBuf::from(s)
+}
+
+/// Reads an input file, or decrypts its encrypted counterpart if only that one exists.
+/// `None` if there is neither.
+pub fn read_file(pth: &Path) -> Result<Option<String>> {
+    match fs::read_to_string(pth) {
+        Ok(s) => return Ok(Some(s)),
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    let encrypted = encrypted_path(pth);
+    let data = match fs::read(&encrypted) {
+        Ok(data) => data,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    debug!(path = %encrypted.display(), "decrypting input");
+    let read = || -> Result<String> { Ok(String::from_utf8(Key::require()?.decrypt(&data)?)?) };
+    read()
+        .with_context(|| format!("can't read {}", encrypted.display()))
+        .map(Some)
+}
+
+/// How [`normalize`] treats whitespace at the end of lines.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Whitespace {
+    /// Strip trailing whitespace and blank lines, ending the input with a single newline.
+    #[default]
+    Trim,
+    /// Keep all whitespace, for puzzles where it is significant.
+    Keep,
+}
+
+/// Strips a byte order mark, turns CRLF line endings into LF and, unless whitespace is
+/// kept, trims trailing whitespace. Borrows the input if it is normalized already.
+pub fn normalize(inp: &str, whitespace: Whitespace) -> Cow<'_, str> {
+    let inp = inp.strip_prefix('\u{feff}').unwrap_or(inp);
+    match whitespace {
+        Whitespace::Keep if !inp.contains("\r\n") => Cow::Borrowed(inp),
+        Whitespace::Keep => Cow::Owned(inp.replace("\r\n", "\n")),
+        Whitespace::Trim => {
+            let clean = !inp.contains('\r')
+                && !inp.lines().any(|l| l.ends_with(char::is_whitespace))
+                && (inp.is_empty() || (inp.ends_with('\n') && !inp.ends_with("\n\n")));
+            if clean {
+                return Cow::Borrowed(inp);
+            }
+            let mut res = String::with_capacity(inp.len() + 1);
+            for l in inp.lines() {
+                res += l.trim_end();
+                res.push('\n');
+            }
+            res.truncate(res.trim_end().len());
+            if !res.is_empty() {
+                res.push('\n');
+            }
+            Cow::Owned(res)
+        }
+    }
+}
+
+/// Where the input of a single puzzle run comes from.
+pub enum Source {
+    /// The day's file in the inputs directory, or its `-test` example.
+    Store {
+        store: InputStore,
+        test: bool,
+    },
+    File(PathBuf),
+    Stdin,
+    Inline(String),
+}
+
+impl Source {
+    /// Reads the input, `None` if the inputs directory has no file for the day.
+    pub fn read(&self, year: Year, day: Day) -> Result<Option<String>> {
+        match self {
+            Source::Store { store, test } => store.read(year, day, *test),
+            Source::File(pth) => read_file(pth)?
+                .ok_or_else(|| anyhow!("can't read input {}, it does not exist", pth.display()))
+                .map(Some),
+            Source::Stdin => {
+                let mut s = String::new();
+                io::stdin().read_to_string(&mut s)?;
+                Ok(Some(s))
+            }
+            Source::Inline(s) => Ok(Some(s.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{normalize, Whitespace};
+    use std::borrow::Cow;
+
+    #[test]
+    fn normalizes_line_endings_and_whitespace() {
+        let inp = "\u{feff}1 2  \r\n3\r\n\r\n\r\n";
+        assert_eq!(normalize(inp, Whitespace::Trim), "1 2\n3\n");
+        assert_eq!(normalize(inp, Whitespace::Keep), "1 2  \n3\n\n\n");
+        assert_eq!(normalize("1\n2", Whitespace::Trim), "1\n2\n");
+    }
+
+    #[test]
+    fn borrows_normalized_input() {
+        assert!(matches!(
+            normalize("1\n2\n", Whitespace::Trim),
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(
+            normalize("  [D]\n", Whitespace::Keep),
+            Cow::Borrowed(_)
+        ));
+    }
+}
@@ -1,4 +1,35 @@
 extern crate core;
 
+use solver::Solver;
+
+pub mod allocs;
+pub mod answers;
 pub mod aoc2022;
-mod utils;
+pub mod aoc2023;
+pub mod bench;
+pub mod client;
+pub mod config;
+pub mod crypt;
+pub mod error;
+#[cfg(test)]
+mod examples;
+#[cfg(test)]
+mod fake_aoc;
+pub mod generate;
+pub mod input;
+pub mod manifest;
+pub mod parse;
+pub mod report;
+pub mod runner;
+pub mod sanity;
+pub mod scaffold;
+pub mod solver;
+pub mod store;
+pub mod submit;
+#[cfg(test)]
+mod test_dir;
+
+type YearSolvers = fn() -> Vec<Box<dyn Solver>>;
+
+/// Every year module, in the order they are registered.
+const YEARS: &[(u16, YearSolvers)] = &[(2022, aoc2022::solvers), (2023, aoc2023::solvers)];
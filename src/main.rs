@@ -1,44 +1,827 @@
 extern crate core;
 
-use anyhow::{anyhow, Result};
-use aoc::aoc2022;
-use clap::Parser;
-use std::fs::File;
-use std::io::Read;
+use anyhow::{anyhow, Context, Result};
+use aoc::allocs::{self, Counting};
+use aoc::answers::{verify as verify_run, Answers, Verdict, ANSWERS_DIR};
+use aoc::bench::{
+    check_budget, compare, render_changes, render_timings, time_all, BenchHistory, Budgets,
+    Snapshot, BENCH_HISTORY_FILE, BUDGETS_FILE,
+};
+use aoc::client::{fetch_input, Client};
+use aoc::config::{root_path, Config, ROOT_DIR};
+use aoc::crypt::Key;
+use aoc::error::AocError;
+use aoc::input::{encrypted_path, read_file, Source};
+use aoc::manifest::{changed_inputs, Kind, Manifest, Status};
+use aoc::parse::ParseError;
+use aoc::report::{records, render, Format};
+use aoc::runner::{run_all, run_day, DayRange, Outcome, Run, Selection};
+use aoc::sanity;
+use aoc::scaffold::new_day;
+use aoc::solver::{Day, Part, Registry, Year};
+use aoc::submit::{Attempt, History, Reply, HISTORY_FILE};
+use clap::error::ErrorKind;
+use clap::{ArgAction, CommandFactory, Parser, Subcommand};
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+use tracing::Level;
+
+#[global_allocator]
+static ALLOC: Counting = Counting;
 
 #[derive(Parser, Debug)]
+#[command(
+    subcommand_negates_reqs = true,
+    after_help = "Exit codes: 1 other errors, 2 usage, 3 unknown puzzle, 4 missing input, \
+                  5 parse failure, 6 no solution"
+)]
 struct Args {
-    #[arg(short, long)]
-    prob: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Log diagnostics to stderr, `-v` for debug and `-vv` for trace output
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// Count the allocations of parsing and each part, and report them with the timings
+    #[arg(long, global = true)]
+    allocs: bool,
+
+    #[arg(short, long, required = true)]
+    prob: Option<String>,
 
     #[arg(short, long)]
     test: bool,
+
+    /// Read the input from this file instead of the inputs directory, `-` for stdin
+    #[arg(short, long, conflicts_with_all = ["test", "example"])]
+    input: Option<PathBuf>,
+
+    /// Use this text as the input
+    #[arg(short, long, conflicts_with = "test")]
+    example: Option<String>,
+
+    /// Print the results as table, json, csv, markdown or junit
+    #[arg(long, default_value_t = Format::Table, global = true)]
+    format: Format,
+}
+
+impl Args {
+    /// Exits with a usage error if options of solving a single puzzle are combined with a
+    /// subcommand. Clap can't express this without also rejecting the global options
+    /// before the subcommand.
+    fn check_subcommand(&self) {
+        if self.command.is_none() {
+            return;
+        }
+        let single = [
+            ("--prob", self.prob.is_some()),
+            ("--test", self.test),
+            ("--input", self.input.is_some()),
+            ("--example", self.example.is_some()),
+        ];
+        if let Some((arg, _)) = single.iter().find(|(_, given)| *given) {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    format!("{arg} can't be used with a subcommand"),
+                )
+                .exit();
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run every registered solver of a year and print a summary table
+    Run {
+        #[arg(short, long)]
+        year: Year,
+
+        /// Inclusive range of days, e.g. `3..7`, `20..` or `5`
+        #[arg(short, long, default_value = "1..25")]
+        days: DayRange,
+
+        #[arg(short, long)]
+        part: Option<Part>,
+
+        #[arg(short, long)]
+        test: bool,
+
+        /// Give up on a part after this many seconds, 0 waits forever
+        #[arg(long, default_value_t = 60)]
+        timeout: u64,
+    },
+    /// Check the inputs of a year for mistakes like truncated copies, without solving them
+    Validate {
+        #[arg(short, long)]
+        year: Year,
+
+        /// Inclusive range of days, e.g. `3..7`, `20..` or `5`
+        #[arg(short, long, default_value = "1..25")]
+        days: DayRange,
+
+        #[arg(short, long)]
+        test: bool,
+    },
+    /// Scaffold the module, inputs and example files of a new day
+    New {
+        year: Year,
+        day: Day,
+
+        /// Puzzle title shown in listings, defaults to `Day N`
+        #[arg(long)]
+        title: Option<String>,
+    },
+    /// Download the personal input of a day into `inputs/`, unless it is already there
+    Fetch { year: Year, day: Day },
+    /// Solve a part and submit its answer, recording the reply in `answers/history.toml`
+    Submit {
+        /// The part to submit as `YYYY-D-P`
+        prob: String,
+
+        /// Submit even if the answer looks suspicious, e.g. zero or the same as part 1
+        #[arg(long)]
+        force: bool,
+    },
+    /// Record and check the hashes of the input files in `inputs/manifest.toml`, and
+    /// encrypt or decrypt them
+    Inputs {
+        #[command(subcommand)]
+        command: InputsCommand,
+    },
+    /// Check every solver against the accepted answers in `answers/YYYY.toml`
+    Verify {
+        /// Only verify this year instead of all registered years
+        #[arg(short, long)]
+        year: Option<Year>,
+
+        /// Give up on a part after this many seconds, 0 waits forever
+        #[arg(long, default_value_t = 60)]
+        timeout: u64,
+    },
+    /// Time the parse and parts of a year's personal inputs, saving or comparing the
+    /// medians with `bench-history.toml`
+    Bench {
+        #[arg(short, long)]
+        year: Year,
+
+        /// Inclusive range of days, e.g. `3..7`, `20..` or `5`
+        #[arg(short, long, default_value = "1..25")]
+        days: DayRange,
+
+        /// How often each phase is run, its median time is kept
+        #[arg(long, default_value_t = 11)]
+        samples: usize,
+
+        /// Save the timings under this label, replacing earlier ones of the same label
+        #[arg(long)]
+        save: Option<String>,
+
+        /// Compare the timings to the ones saved under this label
+        #[arg(long)]
+        compare: Option<String>,
+
+        /// Fail if a phase got slower than the compared one by more than this percentage
+        #[arg(long, default_value_t = 10.0, requires = "compare")]
+        threshold: f64,
+
+        /// Fail if the selected days take longer than this many milliseconds in total,
+        /// instead of the year's `budget_ms` in `bench.toml`
+        #[arg(long)]
+        budget: Option<f64>,
+    },
 }
 
-fn main() -> Result<()> {
-    let Args { prob, test } = Args::parse();
-    let [y, d, p] = prob_into_parts(&prob)?;
+#[derive(Subcommand, Debug)]
+enum InputsCommand {
+    /// Record input files that are not in the manifest yet
+    Record {
+        /// File names within the inputs directory, all input files if none are given
+        files: Vec<String>,
+
+        /// Also record files that changed since they were recorded
+        #[arg(long)]
+        update: bool,
+    },
+    /// List whether every input file still matches the manifest
+    Check,
+    /// Encrypt personal inputs to `YYYY-D.txt.enc` so they can be committed
+    Encrypt {
+        /// File names within the inputs directory, all plaintext personal inputs if none
+        /// are given
+        files: Vec<String>,
 
-    let mut f = get_input(&format!("{y}-{d}"), test)?;
+        /// Keep the plaintext files instead of removing them
+        #[arg(long)]
+        keep: bool,
+    },
+    /// Decrypt `YYYY-D.txt.enc` inputs back to plaintext
+    Decrypt {
+        /// File names within the inputs directory, without `.enc`, all encrypted inputs
+        /// if none are given
+        files: Vec<String>,
 
-    let mut s = String::new();
-    f.read_to_string(&mut s)?;
+        /// Replace plaintext files that already exist
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+fn main() -> ExitCode {
+    match try_main() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            if let Some(AocError::Parse(inner)) = e.downcast_ref::<AocError>() {
+                if let Some(e) = inner.downcast_ref::<ParseError>() {
+                    eprint!("{}", e.snippet());
+                }
+            }
+            ExitCode::from(e.downcast_ref::<AocError>().map_or(1, AocError::exit_code))
+        }
+    }
+}
 
-    let res = aoc2022::exec(&format!("{d}-{p}"), &s)?;
-    println!("{res}");
+fn try_main() -> Result<()> {
+    let args = Args::parse();
+    args.check_subcommand();
+    let Args {
+        command,
+        prob,
+        test,
+        input,
+        example,
+        format,
+        verbose,
+        allocs,
+    } = args;
+    init_tracing(verbose);
+    if allocs {
+        allocs::enable()?;
+    }
+    let config = Config::discover()?;
+    match command {
+        Some(Command::Run {
+            year,
+            days,
+            part,
+            test,
+            timeout,
+        }) => run(
+            &config,
+            Selection { year, days, part },
+            test,
+            timeout,
+            format,
+        ),
+        Some(Command::Verify { year, timeout }) => verify(&config, year, timeout, format),
+        Some(Command::Bench {
+            year,
+            days,
+            samples,
+            save,
+            compare,
+            threshold,
+            budget,
+        }) => bench(
+            &config,
+            Selection {
+                year,
+                days,
+                part: None,
+            },
+            samples,
+            save,
+            compare.map(|label| (label, threshold)),
+            budget,
+        ),
+        Some(Command::Validate { year, days, test }) => validate(&config, year, days, test),
+        Some(Command::New { year, day, title }) => new(&config, year, day, title),
+        Some(Command::Fetch { year, day }) => fetch(&config, year, day),
+        Some(Command::Submit { prob, force }) => submit(&config, &prob, force),
+        Some(Command::Inputs { command }) => inputs(&config, command),
+        None => {
+            if input.is_none() && example.is_none() {
+                warn_changed_inputs(&config.inputs_dir());
+            }
+            let source = match (input, example) {
+                (Some(pth), _) if pth.as_os_str() == "-" => Source::Stdin,
+                (Some(pth), _) => Source::File(pth),
+                (_, Some(example)) => Source::Inline(example),
+                (None, None) => Source::Store {
+                    store: config.store(),
+                    test,
+                },
+            };
+            solve(&prob.expect("required by clap"), &source, format)
+        }
+    }
+}
+
+fn solve(prob: &str, source: &Source, format: Format) -> Result<()> {
+    let (year, day, part) = prob_into_parts(prob)?;
+    let registry = Registry::new();
+    let solvers = match part {
+        Some(part) => vec![registry.get(year, day, part)?],
+        None => Part::ALL
+            .iter()
+            .map(|p| registry.get(year, day, *p))
+            .collect::<Result<_, _>>()?,
+    };
+
+    let s = source
+        .read(year, day)?
+        .ok_or_else(|| AocError::MissingInput {
+            year,
+            day,
+            path: match source {
+                Source::Store { store, test } => Some(store.path(year, day, *test)),
+                _ => None,
+            },
+        })?;
+
+    let runs = run_day(&solvers, &s, None);
+    // a single answer is printed bare, so it can be piped into other tools
+    if part.is_some() && format == Format::Table {
+        if let Some(run) = runs.first() {
+            if let Outcome::Solved(res) = &run.outcome {
+                println!("{res}");
+            }
+            if let (Some(parse), Some(solve)) = (run.parse_allocs, run.allocs) {
+                eprintln!("allocations / bytes allocated / peak live bytes");
+                eprintln!("parse: {parse}\n{prob}: {solve}");
+            }
+        }
+    } else {
+        let answers = match source {
+            Source::Store { test: false, .. } => {
+                Some(Answers::load(&root_path(ANSWERS_DIR), year)?)
+            }
+            _ => None,
+        };
+        print!("{}", render(format, &records(&runs, answers.as_ref())));
+    }
+    print_warnings(&runs);
+    check_failed(&runs)
+}
+
+fn run(config: &Config, sel: Selection, test: bool, timeout: u64, format: Format) -> Result<()> {
+    warn_changed_inputs(&config.inputs_dir());
+    let registry = Registry::new();
+    let runs = run_all(
+        &registry,
+        &sel,
+        &config.store(),
+        test,
+        timeout_secs(timeout),
+    )?;
+    // the accepted answers are for the personal inputs, not the examples
+    let answers = if test {
+        None
+    } else {
+        Some(Answers::load(&root_path(ANSWERS_DIR), sel.year)?)
+    };
+    print!("{}", render(format, &records(&runs, answers.as_ref())));
+    print_warnings(&runs);
+    check_failed(&runs)
+}
+
+/// Times the selection, then compares with and saves to the bench history, failing on
+/// parts that did not finish, regressions over the threshold or a total over the budget.
+fn bench(
+    config: &Config,
+    sel: Selection,
+    samples: usize,
+    save: Option<String>,
+    compare_to: Option<(String, f64)>,
+    budget: Option<f64>,
+) -> Result<()> {
+    warn_changed_inputs(&config.inputs_dir());
+    let budget = match budget {
+        Some(budget) => Some(budget),
+        None => Budgets::load(&root_path(BUDGETS_FILE))?.get(sel.year),
+    };
+    let history_file = &root_path(BENCH_HISTORY_FILE);
+    let mut history = BenchHistory::load(history_file)?;
+    let run = time_all(&Registry::new(), &sel, &config.store(), samples)?;
+    for failure in &run.failures {
+        eprintln!("not timing {failure}");
+    }
+    let mut regressions = 0;
+    match &compare_to {
+        Some((label, threshold)) => {
+            let base = history.get(label).ok_or_else(|| {
+                anyhow!(
+                    "no timings saved as '{label}' in {}",
+                    history_file.display()
+                )
+            })?;
+            let changes = compare(base, &run);
+            print!("{}", render_changes(base, &changes, *threshold));
+            regressions = changes.iter().filter(|c| c.regressed(*threshold)).count();
+        }
+        None => print!("{}", render_timings(&run.timings)),
+    }
+    if let Some(label) = save {
+        history.record(Snapshot::new(&label, run.timings.clone()));
+        history.save(history_file)?;
+        eprintln!("saved as '{label}' in {}", history_file.display());
+    }
+    let mut failures = vec![];
+    if let Some((label, threshold)) = compare_to {
+        if regressions > 0 {
+            failures.push(format!(
+                "{regressions} timings regressed by more than {threshold}% or are missing \
+                 since '{label}'"
+            ));
+        }
+    }
+    match budget.map(|budget| check_budget(&run, budget)) {
+        Some(Err(e)) => failures.push(format!("{} is over budget: {e}", sel.year)),
+        None if !run.failures.is_empty() => {
+            failures.push(format!("{} parts did not finish", run.failures.len()))
+        }
+        _ => {}
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("{}", failures.join(", ")))
+    }
+}
+
+fn verify(config: &Config, year: Option<Year>, timeout: u64, format: Format) -> Result<()> {
+    let registry = Registry::new();
+    let years = match year {
+        Some(year) => vec![year],
+        None => registry.years().to_vec(),
+    };
+    let store = config.store();
+    let dir = store.dir();
+    warn_changed_inputs(dir);
+    let mut manifest = Manifest::load(dir)?;
+    let mut failed = 0;
+    let mut report = vec![];
+    for year in years {
+        let answers = Answers::load(&root_path(ANSWERS_DIR), year)?;
+        let sel = Selection {
+            year,
+            days: DayRange::default(),
+            part: None,
+        };
+        let runs = run_all(&registry, &sel, &store, false, timeout_secs(timeout))?;
+        report.extend(records(&runs, Some(&answers)));
+        for run in &runs {
+            let status = match verify_run(&answers, run) {
+                Verdict::Pass => "pass".to_string(),
+                Verdict::Fail { expected, actual } => {
+                    failed += 1;
+                    format!("FAIL expected '{expected}' got '{actual}'")
+                }
+                Verdict::Missing { actual } => format!("missing answer, got '{actual}'"),
+                Verdict::NoInput => "no input".to_string(),
+                Verdict::Error(outcome) => {
+                    failed += 1;
+                    outcome.to_string()
+                }
+            };
+            if format == Format::Table {
+                println!(
+                    "{year}-{}-{}: {status}",
+                    run.solver.day(),
+                    run.solver.part()
+                );
+            }
+        }
+        // inputs giving the accepted answers are worth remembering
+        for day in runs.chunk_by(|a, b| a.solver.day() == b.solver.day()) {
+            if !day
+                .iter()
+                .all(|r| matches!(verify_run(&answers, r), Verdict::Pass))
+            {
+                continue;
+            }
+            let pth = store.path(year, day[0].solver.day(), false);
+            let name = file_name(&pth);
+            if manifest.get(&name).is_some() {
+                continue;
+            }
+            if let Some(content) = read_file(&pth)? {
+                manifest.record(&name, content.as_bytes());
+                // keep machine-readable output on stdout parseable
+                match format {
+                    Format::Table => println!("recorded {name} in the manifest"),
+                    _ => eprintln!("recorded {name} in the manifest"),
+                }
+            }
+        }
+    }
+    manifest.save(dir)?;
+    if format != Format::Table {
+        print!("{}", render(format, &report));
+    }
+    if failed > 0 {
+        return Err(anyhow!("{failed} puzzles did not match their answers"));
+    }
+    Ok(())
+}
+
+fn validate(config: &Config, year: Year, days: DayRange, test: bool) -> Result<()> {
+    warn_changed_inputs(&config.inputs_dir());
+    let registry = Registry::new();
+    let mut invalid = 0;
+    for solver in registry
+        .year(year)?
+        .filter(|s| s.part() == Part::One && days.contains(s.day()))
+    {
+        let day = solver.day();
+        let Some(inp) = config.store().read(year, day, test)? else {
+            println!("{year}-{day}: no input");
+            continue;
+        };
+        match solver.validate(&inp) {
+            None => println!("{year}-{day}: no validator"),
+            Some(errs) if errs.is_empty() => println!("{year}-{day}: ok"),
+            Some(errs) => {
+                invalid += 1;
+                println!("{year}-{day}: {} problems", errs.len());
+                for e in errs {
+                    print!("{}", e.snippet());
+                }
+            }
+        }
+    }
+    if invalid > 0 {
+        return Err(anyhow!("{invalid} inputs are invalid"));
+    }
+    Ok(())
+}
+
+fn new(config: &Config, year: Year, day: Day, title: Option<String>) -> Result<()> {
+    let title = title.unwrap_or_else(|| format!("Day {day}"));
+    for pth in new_day(Path::new(ROOT_DIR), &config.inputs_dir(), year, day, &title)? {
+        println!("created {}", pth.display());
+    }
+    Ok(())
+}
+
+fn fetch(config: &Config, year: Year, day: Day) -> Result<()> {
+    let cached = config.store().path(year, day, false);
+    if cached.exists() || encrypted_path(&cached).exists() {
+        println!("{} is already cached", cached.display());
+        return Ok(());
+    }
+    let client = Client::new(&config.base_url(), &config.session()?);
+    match fetch_input(&client, &config.inputs_dir(), year, day)? {
+        (pth, true) => {
+            let mut manifest = Manifest::load(&config.inputs_dir())?;
+            manifest.record(&file_name(&pth), &fs::read(&pth)?);
+            manifest.save(&config.inputs_dir())?;
+            println!("fetched {}", pth.display())
+        }
+        (pth, false) => println!("{} is already cached", pth.display()),
+    }
     Ok(())
 }
 
-fn get_input(prob: &str, test: bool) -> Result<File> {
-    let t_str = if test { "-test" } else { "" };
-    let pth = format!("inputs/{prob}{t_str}.txt");
-    Ok(File::open(pth)?)
+fn inputs(config: &Config, command: InputsCommand) -> Result<()> {
+    let store = config.store();
+    let dir = store.dir();
+    let mut manifest = Manifest::load(dir)?;
+    match command {
+        InputsCommand::Record { files, update } => {
+            let files = if files.is_empty() {
+                store.file_names()?
+            } else {
+                files
+            };
+            for name in files {
+                let pth = dir.join(&name);
+                let content = read_file(&pth)?
+                    .ok_or_else(|| anyhow!("can't read {}, it does not exist", pth.display()))?;
+                let content = content.as_bytes();
+                match manifest.check(&name, content) {
+                    Status::Matches => {}
+                    Status::Changed { .. } if !update => {
+                        println!("{name} changed, pass --update to record it anyway")
+                    }
+                    _ => {
+                        manifest.record(&name, content);
+                        println!("recorded {} {name}", Kind::of(&name));
+                    }
+                }
+            }
+            manifest.save(dir)
+        }
+        InputsCommand::Check => {
+            let mut changed = 0;
+            for (name, status) in manifest.check_dir(dir)? {
+                changed +=
+                    matches!(status, Status::Changed { .. } | Status::Unreadable(_)) as usize;
+                println!("{} {name}: {status}", Kind::of(&name));
+            }
+            if changed > 0 {
+                return Err(anyhow!(
+                    "{changed} inputs changed since they were recorded or can't be read"
+                ));
+            }
+            Ok(())
+        }
+        InputsCommand::Encrypt { files, keep } => {
+            let key = Key::require()?;
+            let files = if files.is_empty() {
+                store
+                    .file_names()?
+                    .into_iter()
+                    .filter(|n| Kind::of(n) == Kind::Input && dir.join(n).exists())
+                    .collect()
+            } else {
+                files
+            };
+            for name in files {
+                let pth = dir.join(&name);
+                let plain =
+                    fs::read(&pth).with_context(|| format!("can't read {}", pth.display()))?;
+                let encrypted = encrypted_path(&pth);
+                fs::write(&encrypted, key.encrypt(&plain)?)?;
+                if !keep {
+                    fs::remove_file(&pth)?;
+                }
+                println!("encrypted {}", encrypted.display());
+            }
+            Ok(())
+        }
+        InputsCommand::Decrypt { files, force } => {
+            let key = Key::require()?;
+            let files = if files.is_empty() {
+                store
+                    .file_names()?
+                    .into_iter()
+                    .filter(|n| encrypted_path(&dir.join(n)).exists())
+                    .collect()
+            } else {
+                files
+            };
+            for name in files {
+                let pth = dir.join(&name);
+                let encrypted = encrypted_path(&pth);
+                if pth.exists() && !force {
+                    eprintln!("skipping {}, it already exists", pth.display());
+                    continue;
+                }
+                let data = fs::read(&encrypted)
+                    .with_context(|| format!("can't read {}", encrypted.display()))?;
+                let plain = key
+                    .decrypt(&data)
+                    .with_context(|| format!("can't decrypt {}", encrypted.display()))?;
+                fs::write(&pth, plain)?;
+                println!("decrypted {}", pth.display());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Warns about inputs that were changed since they were recorded in the manifest, run by
+/// the commands reading inputs.
+fn warn_changed_inputs(dir: &Path) {
+    for changed in changed_inputs(dir) {
+        eprintln!("warning: {changed}");
+    }
+}
+
+fn file_name(pth: &Path) -> String {
+    pth.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn submit(config: &Config, prob: &str, force: bool) -> Result<()> {
+    let (year, day, part) = match prob_into_parts(prob)? {
+        (year, day, Some(part)) => (year, day, part),
+        _ => return Err(anyhow!("submitting requires a part, e.g. {prob}-1")),
+    };
+    let registry = Registry::new();
+    let solver = registry.get(year, day, part)?;
+    let store = config.store();
+    let inp = store
+        .read(year, day, false)?
+        .ok_or_else(|| AocError::MissingInput {
+            year,
+            day,
+            path: Some(store.path(year, day, false)),
+        })?;
+    let answer = solver.solve(&inp)?;
+    let first = match part {
+        Part::One => None,
+        Part::Two => registry.get(year, day, Part::One)?.solve(&inp).ok(),
+    };
+    let warnings = sanity::check(solver, &answer, first.as_deref());
+    if !warnings.is_empty() {
+        for w in &warnings {
+            eprintln!("warning: {year}-{day}-{part}: {w}");
+        }
+        if !force {
+            return Err(anyhow!(
+                "not submitting the suspicious answer {answer}, pass --force to submit it anyway"
+            ));
+        }
+    }
+
+    let history_file = root_path(ANSWERS_DIR).join(HISTORY_FILE);
+    let mut history = History::load(&history_file)?;
+    history.check(year, day, part, &answer)?;
+
+    let client = Client::new(&config.base_url(), &config.session()?);
+    let (reply, wait) = client.submit(year, day, part, &answer)?;
+    let mut attempt = Attempt::new(year, day, part, &answer, reply);
+    attempt.wait_secs = wait.map(|w| w.as_secs());
+    history.record(attempt);
+    history.save(&history_file)?;
+
+    match reply {
+        Reply::Correct => {
+            println!("{answer} is correct");
+            Ok(())
+        }
+        Reply::RateLimited => Err(anyhow!(
+            "rate limited, wait {}s before submitting again",
+            wait.map_or(60, |w| w.as_secs())
+        )),
+        Reply::WrongLevel => Err(anyhow!("{year}-{day}-{part} is already solved or locked")),
+        Reply::TooHigh => Err(anyhow!("{answer} is too high")),
+        Reply::TooLow => Err(anyhow!("{answer} is too low")),
+        Reply::Incorrect => Err(anyhow!("{answer} is not the right answer")),
+    }
+}
+
+/// Warns about suspicious answers, comparing part 2 to part 1 if both ran.
+fn print_warnings(runs: &[Run]) {
+    for run in runs {
+        let Outcome::Solved(answer) = &run.outcome else {
+            continue;
+        };
+        let (year, day, part) = (run.solver.year(), run.solver.day(), run.solver.part());
+        let first = runs
+            .iter()
+            .filter(|_| part == Part::Two)
+            .find(|r| r.solver.day() == day && r.solver.part() == Part::One)
+            .and_then(|r| match &r.outcome {
+                Outcome::Solved(a) => Some(a.as_str()),
+                _ => None,
+            });
+        for w in sanity::check(run.solver, answer, first) {
+            eprintln!("warning: {year}-{day}-{part}: {w}");
+        }
+    }
+}
+
+/// Fails with the error of the first failed run, so its exit code tells what went wrong.
+fn check_failed(runs: &[Run]) -> Result<()> {
+    let failed: Vec<_> = runs.iter().filter(|r| r.outcome.is_failure()).collect();
+    let Some(first) = failed.first() else {
+        return Ok(());
+    };
+    let s = first.solver;
+    let prob = format!("{}-{}-{}", s.year(), s.day(), s.part());
+    let summary = format!("{} of {} puzzles failed", failed.len(), runs.len());
+    Err(match first.outcome.error() {
+        Some(e) if runs.len() == 1 => e,
+        Some(e) => e.context(format!("{summary}, {prob}")),
+        None if runs.len() == 1 => anyhow!("{prob} {}", first.outcome),
+        None => anyhow!("{summary}"),
+    })
+}
+
+/// Routes diagnostics of the solvers to stderr, so they never mix with the answers.
+fn init_tracing(verbose: u8) {
+    let level = match verbose {
+        0 => Level::WARN,
+        1 => Level::DEBUG,
+        _ => Level::TRACE,
+    };
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal())
+        .init();
+}
+
+/// A timeout in seconds as given on the command line, where 0 disables it.
+fn timeout_secs(secs: u64) -> Option<Duration> {
+    (secs > 0).then(|| Duration::from_secs(secs))
 }
 
-fn prob_into_parts(prob: &str) -> Result<[&str; 3]> {
+/// Splits `YYYY-D-P` into its parts, or `YYYY-D` to run both parts of a day.
+fn prob_into_parts(prob: &str) -> Result<(Year, Day, Option<Part>)> {
     let parts: Vec<_> = prob.split('-').collect();
-    if parts.len() != 3 {
-        return Err(anyhow!("illegal problem def {prob}"));
+    match parts[..] {
+        [y, d] => Ok((y.parse()?, d.parse()?, None)),
+        [y, d, p] => Ok((y.parse()?, d.parse()?, Some(p.parse()?))),
+        _ => Err(anyhow!("illegal problem def {prob}")),
     }
-    Ok([parts[0], parts[1], parts[2]])
 }
@@ -0,0 +1,293 @@
+use crate::crypt::Key;
+use crate::input::read_file;
+use crate::store::InputStore;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Kept next to the inputs it describes.
+pub const MANIFEST_FILE: &str = "manifest.toml";
+
+/// What an input file looked like when it was recorded.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub sha256: String,
+    pub lines: usize,
+    pub bytes: usize,
+}
+
+impl Entry {
+    pub fn of(content: &[u8]) -> Self {
+        Self {
+            sha256: Sha256::digest(content)
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect(),
+            // like `str::lines`, a final line needs no line break
+            lines: content.iter().filter(|b| **b == b'\n').count()
+                + usize::from(!content.is_empty() && !content.ends_with(b"\n")),
+            bytes: content.len(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Kind {
+    /// A personal puzzle input, `YYYY-D.txt`.
+    Input,
+    /// An example from the puzzle text, `YYYY-D-test[-name].txt`.
+    Example,
+}
+
+impl Kind {
+    pub fn of(file_name: &str) -> Self {
+        if file_name.contains("-test") {
+            Kind::Example
+        } else {
+            Kind::Input
+        }
+    }
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Input => write!(f, "input"),
+            Kind::Example => write!(f, "example"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Status {
+    Matches,
+    Unrecorded,
+    Changed {
+        recorded: Entry,
+        actual: Entry,
+    },
+    /// The file exists but could not be read or decrypted.
+    Unreadable(String),
+    /// The file was recorded but is not there, e.g. an input that is neither kept in
+    /// plaintext nor encrypted in this checkout.
+    Missing,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Matches => write!(f, "matches"),
+            Status::Unrecorded => write!(f, "not recorded"),
+            Status::Changed { recorded, actual } => write!(
+                f,
+                "changed since it was recorded, {} lines and {} bytes are now {} and {}",
+                recorded.lines, recorded.bytes, actual.lines, actual.bytes
+            ),
+            Status::Unreadable(e) => write!(f, "can't be read: {e}"),
+            Status::Missing => write!(f, "recorded but missing, skipped"),
+        }
+    }
+}
+
+/// Hashes of the input files in `inputs/manifest.toml`, to notice inputs that were edited
+/// by accident. Personal inputs and examples are kept in separate tables.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    #[serde(default)]
+    inputs: BTreeMap<String, Entry>,
+    #[serde(default)]
+    examples: BTreeMap<String, Entry>,
+}
+
+impl Manifest {
+    /// Loads the manifest of an inputs directory, an absent file means an empty manifest.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let pth = dir.join(MANIFEST_FILE);
+        match fs::read_to_string(&pth) {
+            Ok(s) => toml::from_str(&s)
+                .with_context(|| format!("illegal manifest file {}", pth.display())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join(MANIFEST_FILE), toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    fn entries(&self, kind: Kind) -> &BTreeMap<String, Entry> {
+        match kind {
+            Kind::Input => &self.inputs,
+            Kind::Example => &self.examples,
+        }
+    }
+
+    pub fn get(&self, file_name: &str) -> Option<&Entry> {
+        self.entries(Kind::of(file_name)).get(file_name)
+    }
+
+    /// Records the content of a file, replacing what was recorded for it before.
+    pub fn record(&mut self, file_name: &str, content: &[u8]) {
+        let entries = match Kind::of(file_name) {
+            Kind::Input => &mut self.inputs,
+            Kind::Example => &mut self.examples,
+        };
+        entries.insert(file_name.to_string(), Entry::of(content));
+    }
+
+    pub fn check(&self, file_name: &str, content: &[u8]) -> Status {
+        let actual = Entry::of(content);
+        match self.get(file_name) {
+            None => Status::Unrecorded,
+            // counts recorded by older versions may differ for the same content
+            Some(recorded) if recorded.sha256 == actual.sha256 => Status::Matches,
+            Some(recorded) => Status::Changed {
+                recorded: recorded.clone(),
+                actual,
+            },
+        }
+    }
+
+    /// Checks every input and example file of the directory, sorted by name. Encrypted
+    /// inputs are skipped if there is no key to decrypt them, a file failing to read does
+    /// not stop the others from being checked. Recorded files that are missing are listed
+    /// too.
+    pub fn check_dir(&self, dir: &Path) -> Result<Vec<(String, Status)>> {
+        // a broken key shows up as the encrypted inputs being unreadable
+        let has_key = !matches!(Key::discover(), Ok(None));
+        let names = InputStore::new(dir).file_names()?;
+        let mut res = vec![];
+        for name in &names {
+            let pth = dir.join(name);
+            if !pth.exists() && !has_key {
+                continue;
+            }
+            let status = match read_file(&pth) {
+                Ok(Some(content)) => self.check(name, content.as_bytes()),
+                Ok(None) => continue,
+                Err(e) => Status::Unreadable(format!("{e:#}")),
+            };
+            res.push((name.clone(), status));
+        }
+        let missing = self
+            .inputs
+            .keys()
+            .chain(self.examples.keys())
+            .filter(|name| !names.contains(name));
+        res.extend(missing.map(|name| (name.clone(), Status::Missing)));
+        res.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(res)
+    }
+}
+
+/// Describes every input file that no longer matches the manifest or can't be read. This
+/// is only a warning, so failing to check the directory at all is described too.
+pub fn changed_inputs(dir: &Path) -> Vec<String> {
+    let statuses = Manifest::load(dir).and_then(|manifest| manifest.check_dir(dir));
+    match statuses {
+        Ok(statuses) => statuses
+            .into_iter()
+            .filter(|(_, status)| {
+                !matches!(
+                    status,
+                    Status::Matches | Status::Unrecorded | Status::Missing
+                )
+            })
+            .map(|(name, status)| format!("{} {name} {status}", Kind::of(&name)))
+            .collect(),
+        Err(e) => vec![format!(
+            "can't check the inputs against the manifest: {e:#}"
+        )],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{changed_inputs, Entry, Manifest, Status};
+    use crate::test_dir::TestDir;
+    use std::fs;
+
+    #[test]
+    fn entries_describe_content() {
+        let entry = Entry::of(b"1\n2\n\n3\n");
+        assert_eq!(entry.lines, 4);
+        assert_eq!(entry.bytes, 7);
+        assert_eq!(Entry::of(b"1\n\n3").lines, 3);
+        assert_eq!(
+            Entry::of(b"").sha256,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn detects_changed_inputs() {
+        let dir = TestDir::new("manifest");
+        fs::write(dir.join("2022-1.txt"), "1\n2\n3\n").unwrap();
+        fs::write(dir.join("2022-1-test.txt"), "1\n").unwrap();
+
+        let mut manifest = Manifest::load(&dir).unwrap();
+        manifest.record("2022-1.txt", b"1\n2\n3\n");
+        manifest.record("2022-1-test.txt", b"1\n");
+        manifest.save(&dir).unwrap();
+        let saved = fs::read_to_string(dir.join("manifest.toml")).unwrap();
+        assert!(saved.contains("[inputs.\"2022-1.txt\"]"));
+        assert!(saved.contains("[examples.\"2022-1-test.txt\"]"));
+        assert!(changed_inputs(&dir).is_empty());
+
+        fs::write(dir.join("2022-1.txt"), "1\n2\n").unwrap();
+        fs::write(dir.join("2022-2.txt"), "new\n").unwrap();
+        let statuses = Manifest::load(&dir).unwrap().check_dir(&dir).unwrap();
+        assert!(matches!(statuses[0], (_, Status::Matches)));
+        assert!(matches!(statuses[1], (_, Status::Changed { .. })));
+        assert_eq!(statuses[2], ("2022-2.txt".to_string(), Status::Unrecorded));
+        assert_eq!(
+            changed_inputs(&dir),
+            vec!["input 2022-1.txt changed since it was recorded, 3 lines and 6 bytes are now 2 and 4"]
+        );
+    }
+
+    #[test]
+    fn unreadable_inputs_are_only_reported() {
+        let dir = TestDir::new("manifest-unreadable");
+        fs::write(dir.join("2022-1.txt"), [0xff, b'\n']).unwrap();
+        fs::write(dir.join("2022-2.txt"), "1\n").unwrap();
+        let statuses = Manifest::default().check_dir(&dir).unwrap();
+        assert!(matches!(statuses[0], (_, Status::Unreadable(_))));
+        assert_eq!(statuses[1], ("2022-2.txt".to_string(), Status::Unrecorded));
+        let changed = changed_inputs(&dir);
+        assert_eq!(changed.len(), 1);
+        assert!(changed[0].starts_with("input 2022-1.txt can't be read: "));
+
+        fs::write(dir.join("manifest.toml"), "not toml").unwrap();
+        let changed = changed_inputs(&dir);
+        assert_eq!(changed.len(), 1);
+        assert!(changed[0].starts_with("can't check the inputs against the manifest: "));
+    }
+
+    #[test]
+    fn missing_inputs_are_skipped() {
+        let dir = TestDir::new("manifest-missing");
+        fs::write(dir.join("2022-2.txt"), "1\n").unwrap();
+        let mut manifest = Manifest::default();
+        manifest.record("2022-1.txt", b"1\n2\n");
+        manifest.record("2022-2.txt", b"1\n");
+        manifest.save(&dir).unwrap();
+        let statuses = manifest.check_dir(&dir).unwrap();
+        assert_eq!(
+            statuses,
+            [
+                ("2022-1.txt".to_string(), Status::Missing),
+                ("2022-2.txt".to_string(), Status::Matches),
+            ]
+        );
+        assert!(changed_inputs(&dir).is_empty());
+    }
+}
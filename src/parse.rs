@@ -0,0 +1,217 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Where in the input a [`ParseError`] occurred, lines and columns count from 1.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+    /// The whole offending line.
+    pub text: String,
+}
+
+/// A parse failure pointing at the part of the input it was raised for.
+///
+/// Errors are created for a slice of the input, e.g. a single field of a line, and
+/// remember where that slice lives, so [`ParseError::within`] can later locate it in the
+/// complete input without every parser having to pass the input around.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub hint: Option<String>,
+    pub location: Option<Location>,
+    /// Address and length in bytes of the slice the error was raised for.
+    addr: usize,
+    len: usize,
+}
+
+impl ParseError {
+    pub fn new(at: &str, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            hint: None,
+            location: None,
+            addr: at.as_ptr() as usize,
+            len: at.len(),
+        }
+    }
+
+    pub fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    /// Locates the error in `inp`, keeping its location if it was raised for another string.
+    pub fn within(mut self, inp: &str) -> Self {
+        let Some(offset) = self
+            .addr
+            .checked_sub(inp.as_ptr() as usize)
+            .filter(|o| *o <= inp.len())
+        else {
+            return self;
+        };
+        let start = inp[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let end = inp[offset..].find('\n').map_or(inp.len(), |i| offset + i);
+        self.location = Some(Location {
+            line: inp[..offset].matches('\n').count() + 1,
+            column: inp[start..offset].chars().count() + 1,
+            text: inp[start..end].trim_end_matches('\r').to_string(),
+        });
+        self
+    }
+
+    /// Renders the offending line with a caret under the error and the hint, if any.
+    pub fn snippet(&self) -> String {
+        let mut res = String::new();
+        if let Some(Location { line, column, text }) = &self.location {
+            let start = text
+                .char_indices()
+                .nth(column - 1)
+                .map_or(text.len(), |(i, _)| i);
+            let width = text
+                .get(start..(start + self.len).min(text.len()))
+                .map_or(0, |s| s.chars().count());
+            let pad = " ".repeat(line.to_string().len());
+            res += &format!("{pad} --> line {line}, column {column}\n");
+            res += &format!("{pad} |\n");
+            res += &format!("{line} | {text}\n");
+            res += &format!(
+                "{pad} | {}{} {}\n",
+                " ".repeat(column - 1),
+                "^".repeat(width.max(1)),
+                self.message
+            );
+        }
+        if let Some(hint) = &self.hint {
+            res += &format!("  = hint: {hint}\n");
+        }
+        res
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some(Location { line, column, .. }) => {
+                write!(f, "line {line}, column {column}: {}", self.message)
+            }
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses `s` as a number, or any other [`FromStr`], pointing at `s` if that fails.
+pub fn number<T: FromStr>(s: &str) -> Result<T, ParseError> {
+    s.parse()
+        .map_err(|_| ParseError::new(s, format!("invalid number '{s}'")))
+}
+
+/// Splits `s` at the first `sep`, pointing at the end of `s` if it has none.
+pub fn split_once<'a>(s: &'a str, sep: &str) -> Result<(&'a str, &'a str), ParseError> {
+    s.split_once(sep)
+        .ok_or_else(|| ParseError::new(&s[s.len()..], format!("expected {sep:?}")))
+}
+
+/// The first character of `s` that is not `valid`, as a slice of `s`.
+pub fn first_invalid(s: &str, valid: impl Fn(char) -> bool) -> Option<&str> {
+    s.char_indices()
+        .find(|(_, c)| !valid(*c))
+        .map(|(i, c)| &s[i..i + c.len_utf8()])
+}
+
+/// Parses each non-empty, trimmed line of `inp` with `f`.
+///
+/// Errors other than [`ParseError`] are attributed to the whole line they were raised for.
+pub fn lines<T>(
+    inp: &str,
+    mut f: impl FnMut(&str) -> anyhow::Result<T>,
+) -> Result<Vec<T>, ParseError> {
+    inp.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| f(l).map_err(|e| locate(e, inp, l)))
+        .collect()
+}
+
+/// Checks each non-empty, trimmed line of `inp` with `f`, collecting every failure.
+pub fn check_lines(inp: &str, mut f: impl FnMut(&str) -> anyhow::Result<()>) -> Vec<ParseError> {
+    inp.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .filter_map(|l| f(l).err().map(|e| locate(e, inp, l)))
+        .collect()
+}
+
+fn locate(e: anyhow::Error, inp: &str, line: &str) -> ParseError {
+    match e.downcast::<ParseError>() {
+        Ok(e) => e.within(inp),
+        Err(e) => ParseError::new(line, format!("{e:#}")).within(inp),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_lines, lines, number, split_once, Location, ParseError};
+
+    #[test]
+    fn locates_fields_in_input() {
+        let inp = "1-2\n3-x\n";
+        let err = lines(inp, |l| {
+            let (a, b) = split_once(l, "-")?;
+            Ok((number::<u32>(a)?, number::<u32>(b)?))
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.location,
+            Some(Location {
+                line: 2,
+                column: 3,
+                text: "3-x".to_string()
+            })
+        );
+        assert_eq!(err.to_string(), "line 2, column 3: invalid number 'x'");
+    }
+
+    #[test]
+    fn snippet_points_at_error() {
+        let inp = "A Y\nB\n";
+        let err = lines(inp, |l| Ok(split_once(l, " ")?.0.len()))
+            .unwrap_err()
+            .hint("rounds are written as `A Y`");
+        assert_eq!(
+            err.snippet(),
+            "  --> line 2, column 2\n  |\n2 | B\n  |  ^ expected \" \"\n  = hint: rounds are written as `A Y`\n"
+        );
+        let err = lines("ä 1\nö 2ü\n", |l| {
+            Ok(number::<u8>(split_once(l, " ")?.1)?)
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.snippet(),
+            "  --> line 2, column 3\n  |\n2 | ö 2ü\n  |   ^^ invalid number '2ü'\n"
+        );
+    }
+
+    #[test]
+    fn checks_every_line() {
+        let errs = check_lines("1\nx\n3\ny\n", |l| {
+            number::<u32>(l)?;
+            Ok(())
+        });
+        let lines: Vec<_> = errs
+            .iter()
+            .map(|e| e.location.as_ref().unwrap().line)
+            .collect();
+        assert_eq!(lines, vec![2, 4]);
+    }
+
+    #[test]
+    fn foreign_errors_keep_their_location() {
+        let other = String::from("other");
+        let err = ParseError::new("abc", "bad").within(&other);
+        assert_eq!(err.location, None);
+        assert_eq!(err.to_string(), "bad");
+    }
+}
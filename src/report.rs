@@ -0,0 +1,539 @@
+use crate::allocs::AllocStats;
+use crate::answers::Answers;
+use crate::runner::{Outcome, Run};
+use anyhow::anyhow;
+use iter_tools::Itertools;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// How the results of a run are printed.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum Format {
+    /// Aligned columns for people, with a summary line.
+    #[default]
+    Table,
+    Json,
+    Csv,
+    Markdown,
+    /// JUnit XML, failing the parts whose answer differs from the accepted one.
+    Junit,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "markdown" => Ok(Format::Markdown),
+            "junit" => Ok(Format::Junit),
+            _ => Err(anyhow!(
+                "illegal format '{s}', must be table, json, csv, markdown or junit"
+            )),
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Format::Table => "table",
+            Format::Json => "json",
+            Format::Csv => "csv",
+            Format::Markdown => "markdown",
+            Format::Junit => "junit",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The result of a single part, flattened for reports.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Record {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub title: String,
+    /// One of [`Outcome::STATUSES`].
+    pub status: &'static str,
+    pub answer: Option<String>,
+    /// The accepted answer, if one was recorded.
+    pub expected: Option<String>,
+    /// Whether the answer is the accepted one, `None` if either is missing.
+    pub correct: Option<bool>,
+    /// Only reported on the first part of a day, as both parts share the parse.
+    pub parse_ms: Option<f64>,
+    pub time_ms: Option<f64>,
+    /// Allocations of the parse and of the part, if they were counted. Like `parse_ms`
+    /// the parse is only reported on the first part of a day.
+    pub parse_allocs: Option<AllocStats>,
+    pub allocs: Option<AllocStats>,
+    pub error: Option<String>,
+}
+
+impl Record {
+    fn failed(&self) -> bool {
+        self.error.is_some()
+    }
+
+    fn prob(&self) -> String {
+        format!("{}-{}-{}", self.year, self.day, self.part)
+    }
+}
+
+/// Flattens runs into records, comparing them to `answers` if given.
+pub fn records(runs: &[Run], answers: Option<&Answers>) -> Vec<Record> {
+    runs.iter()
+        .enumerate()
+        .map(|(i, r)| {
+            let (solver, outcome) = (r.solver, &r.outcome);
+            let answer = match outcome {
+                Outcome::Solved(answer) => Some(answer.clone()),
+                _ => None,
+            };
+            let expected = answers
+                .and_then(|a| a.get(solver.day(), solver.part()))
+                .map(str::to_string);
+            let ran = !matches!(outcome, Outcome::NoInput);
+            Record {
+                year: solver.year().get(),
+                day: solver.day().get(),
+                part: solver.part().get(),
+                title: solver.title().to_string(),
+                status: outcome.status(),
+                correct: answer.as_ref().zip(expected.as_ref()).map(|(a, e)| a == e),
+                answer,
+                expected,
+                parse_ms: (ran && first_of_day(runs, i)).then(|| millis(r.parse)),
+                time_ms: ran.then(|| millis(r.elapsed)),
+                parse_allocs: r.parse_allocs.filter(|_| first_of_day(runs, i)),
+                allocs: r.allocs,
+                error: outcome.is_failure().then(|| outcome.to_string()),
+            }
+        })
+        .collect()
+}
+
+/// Both parts of a day share a parse, which is only reported on the first one.
+fn first_of_day(runs: &[Run], i: usize) -> bool {
+    i == 0 || runs[i - 1].solver.day() != runs[i].solver.day()
+}
+
+fn millis(d: std::time::Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+fn format_ms(ms: Option<f64>) -> String {
+    ms.map_or(String::new(), |ms| format!("{ms:.3}ms"))
+}
+
+pub fn render(format: Format, records: &[Record]) -> String {
+    match format {
+        Format::Table => table(records),
+        Format::Json => serde_json::to_string_pretty(records).expect("records serialize") + "\n",
+        Format::Csv => csv(records),
+        Format::Markdown => markdown(records),
+        Format::Junit => junit(records),
+    }
+}
+
+fn format_allocs(stats: Option<AllocStats>) -> String {
+    stats.map_or(String::new(), |s| s.to_string())
+}
+
+fn counted_allocs(records: &[Record]) -> bool {
+    records.iter().any(|r| r.allocs.is_some())
+}
+
+/// The answer is the only left aligned column, and the only one that may span lines.
+const ANSWER_COLUMN: usize = 2;
+
+fn table(records: &[Record]) -> String {
+    let allocs = counted_allocs(records);
+    let rows: Vec<Vec<String>> = records
+        .iter()
+        .map(|r| {
+            let (parse, time) = match r.time_ms {
+                None => ("-".to_string(), "-".to_string()),
+                Some(_) => (format_ms(r.parse_ms), format_ms(r.time_ms)),
+            };
+            let answer = match (&r.answer, &r.error) {
+                (Some(answer), _) => answer.clone(),
+                (None, Some(error)) => error.clone(),
+                (None, None) => r.status.to_string(),
+            };
+            let mut row = vec![r.day.to_string(), r.part.to_string(), answer, parse, time];
+            if allocs {
+                row.extend([format_allocs(r.parse_allocs), format_allocs(r.allocs)]);
+            }
+            row
+        })
+        .collect();
+    let mut header = vec!["day", "part", "answer", "parse", "time"];
+    if allocs {
+        header.extend(["parse mem", "mem"]);
+    }
+    let header: Vec<String> = header.into_iter().map(str::to_string).collect();
+    let widths = std::iter::once(&header)
+        .chain(&rows)
+        .fold(vec![0; header.len()], |mut w, row| {
+            for (w, c) in w.iter_mut().zip(row) {
+                *w = (*w).max(c.lines().map(str::len).max().unwrap_or(0));
+            }
+            w
+        });
+    let mut res = String::new();
+    for row in std::iter::once(&header).chain(&rows) {
+        // an empty answer has no lines but still needs its row
+        let mut lines: Vec<_> = row[ANSWER_COLUMN].lines().collect();
+        if lines.is_empty() {
+            lines.push("");
+        }
+        for (i, line) in lines.into_iter().enumerate() {
+            if i > 0 {
+                res += &format!("{:w$}{line}\n", "", w = widths[0] + widths[1] + 4);
+                continue;
+            }
+            let cells: Vec<_> = row
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(c, (cell, w))| match c {
+                    ANSWER_COLUMN => format!("{line:<w$}"),
+                    _ => format!("{cell:>w$}"),
+                })
+                .collect();
+            res += cells.join("  ").trim_end();
+            res += "\n";
+        }
+    }
+    let total: f64 = records
+        .iter()
+        .map(|r| r.parse_ms.unwrap_or(0.0) + r.time_ms.unwrap_or(0.0))
+        .sum();
+    res += &format!("total: {}\n", format_ms(Some(total)));
+    if allocs {
+        res += "mem: allocations / bytes allocated / peak live bytes\n";
+    }
+    res + &summary(records) + "\n"
+}
+
+/// Counts the records per status, listing the puzzles that did not produce an answer.
+fn summary(records: &[Record]) -> String {
+    let mut summary = vec![];
+    for status in Outcome::STATUSES {
+        let matching: Vec<_> = records.iter().filter(|r| r.status == status).collect();
+        if matching.is_empty() {
+            continue;
+        }
+        if matching[0].failed() {
+            let probs = matching.iter().map(|r| r.prob()).join(", ");
+            summary.push(format!("{} {status} ({probs})", matching.len()));
+        } else {
+            summary.push(format!("{} {status}", matching.len()));
+        }
+    }
+    summary.join(", ")
+}
+
+const CSV_HEADER: [&str; 17] = [
+    "year",
+    "day",
+    "part",
+    "title",
+    "status",
+    "answer",
+    "expected",
+    "correct",
+    "parse_ms",
+    "time_ms",
+    "parse_allocations",
+    "parse_bytes",
+    "parse_peak",
+    "allocations",
+    "bytes",
+    "peak",
+    "error",
+];
+
+fn csv(records: &[Record]) -> String {
+    let field = |s: &str| {
+        if s.contains([',', '"', '\n']) {
+            format!("\"{}\"", s.replace('"', "\"\""))
+        } else {
+            s.to_string()
+        }
+    };
+    let opt = |s: &Option<String>| field(s.as_deref().unwrap_or(""));
+    let ms = |ms: Option<f64>| ms.map_or(String::new(), |ms| format!("{ms:.3}"));
+    let allocs = |s: Option<AllocStats>| {
+        s.map_or([""; 3].map(str::to_string), |s| {
+            [s.allocations, s.bytes, s.peak].map(|n| n.to_string())
+        })
+    };
+    let mut res = CSV_HEADER.join(",") + "\n";
+    for r in records {
+        let [parse_allocations, parse_bytes, parse_peak] = allocs(r.parse_allocs);
+        let [allocations, bytes, peak] = allocs(r.allocs);
+        let row = [
+            r.year.to_string(),
+            r.day.to_string(),
+            r.part.to_string(),
+            field(&r.title),
+            field(r.status),
+            opt(&r.answer),
+            opt(&r.expected),
+            r.correct.map_or(String::new(), |c| c.to_string()),
+            ms(r.parse_ms),
+            ms(r.time_ms),
+            parse_allocations,
+            parse_bytes,
+            parse_peak,
+            allocations,
+            bytes,
+            peak,
+            opt(&r.error),
+        ];
+        res += &(row.join(",") + "\n");
+    }
+    res
+}
+
+fn markdown(records: &[Record]) -> String {
+    let cell = |s: &str| s.replace('|', "\\|").replace('\n', "<br>");
+    let allocs = counted_allocs(records);
+    let mut res = String::from("| year | day | part | title | status | answer | parse | time |");
+    res += if allocs { " parse mem | mem |\n" } else { "\n" };
+    res += "|---:|---:|---:|---|---|---|---:|---:|";
+    res += if allocs { "---:|---:|\n" } else { "\n" };
+    for r in records {
+        let answer = match (&r.answer, &r.error) {
+            (Some(answer), _) => format!("<pre>{}</pre>", cell(answer)),
+            (None, Some(error)) => cell(error),
+            (None, None) => String::new(),
+        };
+        res += &format!(
+            "| {} | {} | {} | {} | {} | {answer} | {} | {} |",
+            r.year,
+            r.day,
+            r.part,
+            cell(&r.title),
+            r.status,
+            format_ms(r.parse_ms),
+            format_ms(r.time_ms),
+        );
+        if allocs {
+            res += &format!(
+                " {} | {} |",
+                format_allocs(r.parse_allocs),
+                format_allocs(r.allocs)
+            );
+        }
+        res += "\n";
+    }
+    res
+}
+
+/// What a test case reports about a record.
+enum Case {
+    Pass,
+    Failure(String),
+    Error(String),
+    Skipped(String),
+}
+
+impl Case {
+    fn of(r: &Record) -> Self {
+        match (&r.answer, &r.expected, &r.error) {
+            (_, _, Some(error)) => Case::Error(error.clone()),
+            (Some(answer), Some(expected), _) if answer != expected => {
+                Case::Failure(format!("expected '{expected}' got '{answer}'"))
+            }
+            (Some(_), Some(_), _) => Case::Pass,
+            (Some(_), None, _) => Case::Skipped("no accepted answer recorded".to_string()),
+            (None, _, _) => Case::Skipped(r.status.to_string()),
+        }
+    }
+}
+
+fn xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "&#10;")
+}
+
+/// One test suite per year with a test case per part.
+fn junit(records: &[Record]) -> String {
+    let secs = |rs: &[&Record]| {
+        rs.iter()
+            .map(|r| r.parse_ms.unwrap_or(0.0) + r.time_ms.unwrap_or(0.0))
+            .sum::<f64>()
+            / 1000.0
+    };
+    let counts = |rs: &[&Record]| {
+        let cases: Vec<_> = rs.iter().map(|r| Case::of(r)).collect();
+        let count = |f: fn(&Case) -> bool| cases.iter().filter(|c| f(c)).count();
+        format!(
+            "tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\"",
+            rs.len(),
+            count(|c| matches!(c, Case::Failure(_))),
+            count(|c| matches!(c, Case::Error(_))),
+            count(|c| matches!(c, Case::Skipped(_))),
+            secs(rs)
+        )
+    };
+    let all: Vec<_> = records.iter().collect();
+    let mut res = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    res += &format!("<testsuites name=\"aoc\" {}>\n", counts(&all));
+    for suite in all.chunk_by(|a, b| a.year == b.year) {
+        let year = suite[0].year;
+        res += &format!("  <testsuite name=\"aoc{year}\" {}>\n", counts(suite));
+        for r in suite {
+            res += &format!(
+                "    <testcase classname=\"aoc{year}.day{:02}\" name=\"{} {}\" time=\"{:.3}\"",
+                r.day,
+                r.prob(),
+                xml(&r.title),
+                secs(&[r])
+            );
+            res += &match Case::of(r) {
+                Case::Pass => "/>\n".to_string(),
+                Case::Failure(msg) => {
+                    format!(
+                        ">\n      <failure message=\"{}\"/>\n    </testcase>\n",
+                        xml(&msg)
+                    )
+                }
+                Case::Error(msg) => {
+                    format!(
+                        ">\n      <error message=\"{}\"/>\n    </testcase>\n",
+                        xml(&msg)
+                    )
+                }
+                Case::Skipped(msg) => {
+                    format!(
+                        ">\n      <skipped message=\"{}\"/>\n    </testcase>\n",
+                        xml(&msg)
+                    )
+                }
+            };
+        }
+        res += "  </testsuite>\n";
+    }
+    res + "</testsuites>\n"
+}
+
+#[cfg(test)]
+mod test {
+    use super::{render, Format, Record};
+
+    fn record(part: u8, answer: &str, expected: Option<&str>) -> Record {
+        Record {
+            year: 2022,
+            day: 10,
+            part,
+            title: "Cathode-Ray Tube".to_string(),
+            status: "solved",
+            answer: Some(answer.to_string()),
+            expected: expected.map(str::to_string),
+            correct: expected.map(|e| e == answer),
+            parse_ms: None,
+            time_ms: Some(1.5),
+            parse_allocs: None,
+            allocs: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn csv_quotes_multi_line_answers() {
+        let csv = render(Format::Csv, &[record(2, "#.\n.#", None)]);
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("2022,10,2,Cathode-Ray Tube,solved,\"#.")
+        );
+        assert!(csv.ends_with(".#\",,,,1.500,,,,,,,\n"));
+    }
+
+    #[test]
+    fn junit_fails_wrong_answers() {
+        let xml = render(
+            Format::Junit,
+            &[record(1, "13140", Some("13140")), record(2, "1", Some("2"))],
+        );
+        assert!(xml.contains("tests=\"2\" failures=\"1\" errors=\"0\" skipped=\"0\""));
+        assert!(xml.contains("name=\"2022-10-1 Cathode-Ray Tube\" time=\"0.002\"/>"));
+        assert!(xml.contains("<failure message=\"expected '2' got '1'\"/>"));
+    }
+
+    #[test]
+    fn junit_counts_errors_and_skips() {
+        let mut failed = record(1, "", None);
+        failed.answer = None;
+        failed.status = "failed";
+        failed.error = Some("error: no <path>".to_string());
+        let xml = render(Format::Junit, &[failed, record(2, "1", None)]);
+        assert!(xml.contains("tests=\"2\" failures=\"0\" errors=\"1\" skipped=\"1\""));
+        assert!(xml.contains("<error message=\"error: no &lt;path&gt;\"/>"));
+        assert!(xml.contains("<skipped message=\"no accepted answer recorded\"/>"));
+    }
+
+    #[test]
+    fn json_keeps_field_names_and_nulls() {
+        let json: serde_json::Value =
+            serde_json::from_str(&render(Format::Json, &[record(1, "13140", None)])).unwrap();
+        let fields: Vec<_> = json[0].as_object().unwrap().keys().cloned().collect();
+        assert_eq!(
+            fields,
+            [
+                "allocs",
+                "answer",
+                "correct",
+                "day",
+                "error",
+                "expected",
+                "parse_allocs",
+                "parse_ms",
+                "part",
+                "status",
+                "time_ms",
+                "title",
+                "year"
+            ]
+        );
+        assert_eq!(json[0]["answer"], "13140");
+        assert_eq!(json[0]["time_ms"], 1.5);
+        for null in ["expected", "correct", "parse_ms", "allocs", "error"] {
+            assert!(json[0][null].is_null(), "{null}");
+        }
+    }
+
+    #[test]
+    fn markdown_escapes_cells() {
+        let mut r = record(2, "a|b\nc", None);
+        r.title = "Pipe | Dream".to_string();
+        let md = render(Format::Markdown, &[r]);
+        let row = md.lines().nth(2).unwrap();
+        assert_eq!(
+            row,
+            "| 2022 | 10 | 2 | Pipe \\| Dream | solved | <pre>a\\|b<br>c</pre> |  | 1.500ms |"
+        );
+    }
+
+    #[test]
+    fn table_keeps_rows_of_empty_answers() {
+        let table = render(Format::Table, &[record(1, "", None), record(2, "1", None)]);
+        let cells: Vec<Vec<_>> = table
+            .lines()
+            .map(|l| l.split_whitespace().collect())
+            .collect();
+        assert_eq!(cells[1], ["10", "1", "1.500ms"], "{table}");
+        assert_eq!(cells[2], ["10", "2", "1", "1.500ms"], "{table}");
+        assert_eq!(cells[3][0], "total:");
+    }
+}
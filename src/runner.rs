@@ -0,0 +1,416 @@
+use crate::allocs::{self, AllocStats};
+use crate::error::AocError;
+use crate::parse::ParseError;
+use crate::solver::{Day, Part, Registry, Solver, Year};
+use crate::store::InputStore;
+use anyhow::{anyhow, Result};
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+use std::panic::{self, AssertUnwindSafe};
+use std::str::FromStr;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{debug, info_span};
+
+/// An inclusive range of days, written as `3..7`, `3..`, `..7` or `5`.
+#[derive(Debug, Copy, Clone)]
+pub struct DayRange {
+    from: u8,
+    to: u8,
+}
+
+impl DayRange {
+    pub fn contains(&self, day: Day) -> bool {
+        (self.from..=self.to).contains(&day.get())
+    }
+}
+
+impl Default for DayRange {
+    fn default() -> Self {
+        Self { from: 1, to: 25 }
+    }
+}
+
+impl FromStr for DayRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let bound = |b: &str, default: u8| -> Result<u8> {
+            if b.is_empty() {
+                Ok(default)
+            } else {
+                Ok(b.parse::<Day>()?.get())
+            }
+        };
+        let (from, to) = match s.split_once("..") {
+            Some((from, to)) => (bound(from, 1)?, bound(to.trim_start_matches('='), 25)?),
+            None => {
+                let day = s.parse::<Day>()?.get();
+                (day, day)
+            }
+        };
+        if from > to {
+            return Err(anyhow!("illegal day range '{s}'"));
+        }
+        Ok(Self { from, to })
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Selection {
+    pub year: Year,
+    pub days: DayRange,
+    pub part: Option<Part>,
+}
+
+impl Selection {
+    pub fn matches(&self, solver: &dyn Solver) -> bool {
+        self.days.contains(solver.day()) && self.part.is_none_or(|p| p == solver.part())
+    }
+}
+
+/// Written as `YYYY`, `YYYY-DAYS` or `YYYY-DAYS-PART`, e.g. `2022-3..7` or `2022-7-2`.
+impl FromStr for Selection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut fields = s.splitn(3, '-');
+        Ok(Self {
+            year: fields.next().unwrap_or_default().parse()?,
+            days: fields.next().map_or(Ok(DayRange::default()), str::parse)?,
+            part: fields.next().map(str::parse).transpose()?,
+        })
+    }
+}
+
+pub enum Outcome {
+    Solved(String),
+    NoInput,
+    Failed(anyhow::Error),
+    /// The solver panicked with this message.
+    Panicked(String),
+    /// The solver did not finish within this time and was left running in the background.
+    TimedOut(Duration),
+}
+
+impl Outcome {
+    /// Every [`Outcome::status`], in the order summaries list them.
+    pub const STATUSES: [&'static str; 5] =
+        ["solved", "no input", "failed", "panicked", "timed out"];
+
+    pub fn status(&self) -> &'static str {
+        match self {
+            Outcome::Solved(_) => "solved",
+            Outcome::NoInput => "no input",
+            Outcome::Failed(_) => "failed",
+            Outcome::Panicked(_) => "panicked",
+            Outcome::TimedOut(_) => "timed out",
+        }
+    }
+
+    /// Whether the solver ran without producing an answer.
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            Outcome::Failed(_) | Outcome::Panicked(_) | Outcome::TimedOut(_)
+        )
+    }
+
+    /// A copy of the error the solver failed with, still an [`AocError`] if it was one.
+    pub fn error(&self) -> Option<anyhow::Error> {
+        match self {
+            Outcome::Failed(e) => Some(clone_error(e)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Solved(answer) => write!(f, "{answer}"),
+            Outcome::NoInput => write!(f, "no input"),
+            Outcome::Failed(e) => write!(f, "error: {e}"),
+            Outcome::Panicked(msg) => write!(f, "panicked: {msg}"),
+            Outcome::TimedOut(limit) => write!(f, "timed out after {limit:?}"),
+        }
+    }
+}
+
+pub struct Run {
+    pub solver: &'static dyn Solver,
+    pub outcome: Outcome,
+    /// Time spent parsing the day's input, shared by both of its parts.
+    pub parse: Duration,
+    pub elapsed: Duration,
+    /// Allocations of the parse and of this part, if they were [counted](crate::allocs).
+    pub parse_allocs: Option<AllocStats>,
+    pub allocs: Option<AllocStats>,
+}
+
+impl Run {
+    /// A run that did not get to report how its parse and solve went.
+    fn unfinished(solver: &'static dyn Solver, outcome: Outcome) -> Self {
+        let elapsed = match outcome {
+            Outcome::TimedOut(limit) => limit,
+            _ => Duration::ZERO,
+        };
+        Self {
+            solver,
+            outcome,
+            parse: Duration::ZERO,
+            elapsed,
+            parse_allocs: None,
+            allocs: None,
+        }
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let res = f();
+    (res, start.elapsed())
+}
+
+/// Copies the message of an error, keeping it an [`AocError::Parse`] or
+/// [`AocError::NoSolution`] if it was one.
+fn clone_error(e: &anyhow::Error) -> anyhow::Error {
+    match e.downcast_ref::<AocError>() {
+        Some(AocError::Parse(inner)) => AocError::Parse(match inner.downcast_ref::<ParseError>() {
+            Some(e) => e.clone().into(),
+            None => anyhow!("{inner:#}"),
+        })
+        .into(),
+        Some(AocError::NoSolution(msg)) => AocError::NoSolution(msg.clone()).into(),
+        _ => anyhow!("{e:#}"),
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(msg) => msg.to_string(),
+        None => payload
+            .downcast_ref::<String>()
+            .cloned()
+            .unwrap_or_else(|| "unknown panic".to_string()),
+    }
+}
+
+/// Parses the input once and runs all given solvers of a single day against it.
+///
+/// The day runs on its own thread so a panic only fails the part it happened in. Each part
+/// may take up to `timeout`, counting the parse against the first one. Once a part times
+/// out the thread is abandoned and the remaining parts time out as well.
+pub fn run_day(solvers: &[&'static dyn Solver], inp: &str, timeout: Option<Duration>) -> Vec<Run> {
+    let Some(first) = solvers.first().copied() else {
+        return vec![];
+    };
+    let (tx, rx) = mpsc::channel();
+    let worker_solvers = solvers.to_vec();
+    let inp = inp.to_string();
+    let day_span = info_span!("day", year = %first.year(), day = %first.day());
+    let spawned = thread::Builder::new()
+        .name(format!("{}-{}", first.year(), first.day()))
+        .spawn(move || {
+            let _day = day_span.entered();
+            let ((parsed, parse_allocs), parse) = timed(|| {
+                info_span!("parse").in_scope(|| {
+                    allocs::measure(|| panic::catch_unwind(AssertUnwindSafe(|| first.parse(&inp))))
+                })
+            });
+            debug!(elapsed = ?parse, "parsed input of {} bytes", inp.len());
+            for solver in worker_solvers {
+                let (outcome, elapsed, allocs) = match &parsed {
+                    Ok(Ok(parsed)) => {
+                        let ((res, allocs), elapsed) = timed(|| {
+                            info_span!("solve", part = %solver.part()).in_scope(|| {
+                                allocs::measure(|| {
+                                    panic::catch_unwind(AssertUnwindSafe(|| {
+                                        solver.solve_parsed(parsed)
+                                    }))
+                                })
+                            })
+                        });
+                        let outcome = match res {
+                            Ok(res) => res.map_or_else(Outcome::Failed, Outcome::Solved),
+                            Err(payload) => Outcome::Panicked(panic_message(payload.as_ref())),
+                        };
+                        (outcome, elapsed, allocs)
+                    }
+                    Ok(Err(e)) => (Outcome::Failed(clone_error(e)), Duration::ZERO, None),
+                    Err(payload) => (
+                        Outcome::Panicked(panic_message(payload.as_ref())),
+                        Duration::ZERO,
+                        None,
+                    ),
+                };
+                debug!(part = %solver.part(), ?elapsed, "{}", outcome.status());
+                let run = Run {
+                    solver,
+                    outcome,
+                    parse,
+                    elapsed,
+                    parse_allocs,
+                    allocs,
+                };
+                if tx.send(run).is_err() {
+                    return;
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        let msg = format!("can't start a thread for the day: {e}");
+        return solvers
+            .iter()
+            .map(|solver| Run::unfinished(*solver, Outcome::Failed(anyhow!("{msg}"))))
+            .collect();
+    }
+
+    let mut abandoned = None;
+    solvers
+        .iter()
+        .map(|solver| {
+            let received = match (abandoned, timeout) {
+                (Some(limit), _) => Err(Outcome::TimedOut(limit)),
+                (None, Some(limit)) => rx.recv_timeout(limit).map_err(|e| match e {
+                    RecvTimeoutError::Timeout => {
+                        abandoned = Some(limit);
+                        Outcome::TimedOut(limit)
+                    }
+                    RecvTimeoutError::Disconnected => {
+                        Outcome::Panicked("the day's thread died".to_string())
+                    }
+                }),
+                (None, None) => rx
+                    .recv()
+                    .map_err(|_| Outcome::Panicked("the day's thread died".to_string())),
+            };
+            received.unwrap_or_else(|outcome| Run::unfinished(*solver, outcome))
+        })
+        .collect()
+}
+
+/// Runs every registered solver matching the selection against its input.
+pub fn run_all(
+    registry: &Registry,
+    sel: &Selection,
+    store: &InputStore,
+    test: bool,
+    timeout: Option<Duration>,
+) -> Result<Vec<Run>> {
+    let mut runs = vec![];
+    let solvers: Vec<_> = registry
+        .year(sel.year)?
+        .filter(|s| sel.matches(*s))
+        .collect();
+    for day in solvers.chunk_by(|a, b| a.day() == b.day()) {
+        match store.read(sel.year, day[0].day(), test)? {
+            Some(inp) => runs.extend(run_day(day, &inp, timeout)),
+            None => runs.extend(
+                day.iter()
+                    .map(|solver| Run::unfinished(*solver, Outcome::NoInput)),
+            ),
+        }
+    }
+    Ok(runs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{run_day, DayRange, Outcome, Selection};
+    use crate::error::AocError;
+    use crate::solver::{Day, Parsed, Part, Registry, Solver, Year};
+    use anyhow::Result;
+    use std::thread;
+    use std::time::Duration;
+
+    /// Panics in its first part and runs away in its second.
+    struct Faulty(Part);
+
+    impl Solver for Faulty {
+        fn year(&self) -> Year {
+            Year::new(2022).unwrap()
+        }
+
+        fn day(&self) -> Day {
+            Day::new(1).unwrap()
+        }
+
+        fn part(&self) -> Part {
+            self.0
+        }
+
+        fn title(&self) -> &'static str {
+            "Faulty"
+        }
+
+        fn parse(&self, inp: &str) -> Result<Parsed> {
+            Ok(Box::new(inp.to_string()))
+        }
+
+        fn solve_parsed(&self, _: &Parsed) -> Result<String> {
+            match self.0 {
+                Part::One => panic!("boom"),
+                Part::Two => {
+                    thread::sleep(Duration::from_secs(10));
+                    Ok("too late".to_string())
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn isolates_panics_and_timeouts() {
+        let runs = run_day(
+            &[&Faulty(Part::One), &Faulty(Part::Two)],
+            "",
+            Some(Duration::from_millis(100)),
+        );
+        assert!(matches!(&runs[0].outcome, Outcome::Panicked(msg) if msg == "boom"));
+        assert!(matches!(runs[1].outcome, Outcome::TimedOut(_)));
+    }
+
+    #[test]
+    fn failures_keep_their_error_kind() {
+        let registry = Registry::new();
+        let day: Vec<_> = registry
+            .year(Year::new(2022).unwrap())
+            .unwrap()
+            .filter(|s| s.day().get() == 1)
+            .collect();
+        for run in run_day(&day, "x y", None) {
+            let e = run.outcome.error().unwrap();
+            assert_eq!(e.downcast_ref::<AocError>().unwrap().exit_code(), 5);
+        }
+    }
+
+    #[test]
+    fn day_ranges() {
+        let contained = |r: &str| {
+            let r: DayRange = r.parse().unwrap();
+            (1..=25)
+                .filter(|d| r.contains(Day::new(*d).unwrap()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(contained("3..7"), vec![3, 4, 5, 6, 7]);
+        assert_eq!(contained("23.."), vec![23, 24, 25]);
+        assert_eq!(contained("..2"), vec![1, 2]);
+        assert_eq!(contained("5"), vec![5]);
+        assert!("7..3".parse::<DayRange>().is_err());
+        assert!("0..3".parse::<DayRange>().is_err());
+    }
+
+    #[test]
+    fn selections() {
+        let sel: Selection = "2022-3..7-2".parse().unwrap();
+        assert_eq!(sel.year.get(), 2022);
+        assert!(sel.days.contains(Day::new(7).unwrap()));
+        assert!(!sel.days.contains(Day::new(8).unwrap()));
+        assert_eq!(sel.part, Some(Part::Two));
+        let sel: Selection = "2022".parse().unwrap();
+        assert!(sel.days.contains(Day::new(25).unwrap()) && sel.part.is_none());
+        assert!("2022-7-3".parse::<Selection>().is_err());
+        assert!("".parse::<Selection>().is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use crate::solver::Solver;
+use std::fmt::{Display, Formatter};
+
+/// Answers with more digits than this are more likely an overflow than the right answer.
+const MAX_DIGITS: usize = 16;
+
+/// Something suspicious about an answer, which is probably wrong but might not be.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Warning {
+    Empty,
+    MultiLine,
+    NotANumber,
+    Zero,
+    Negative,
+    Overflow,
+    SameAsOtherPart,
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            Warning::Empty => "the answer is empty",
+            Warning::MultiLine => "the answer spans several lines but should be a number",
+            Warning::NotANumber => "the answer should be a number",
+            Warning::Zero => "the answer is zero",
+            Warning::Negative => "the answer is negative",
+            Warning::Overflow => "the answer looks like an overflow",
+            Warning::SameAsOtherPart => "both parts have the same answer",
+        };
+        write!(f, "{msg}")
+    }
+}
+
+fn looks_like_overflow(digits: &str) -> bool {
+    let limits = [
+        i32::MAX.to_string(),
+        u32::MAX.to_string(),
+        i64::MAX.to_string(),
+        u64::MAX.to_string(),
+    ];
+    digits.len() > MAX_DIGITS || limits.iter().any(|l| l == digits)
+}
+
+/// Checks the answer of a solver, along with the answer of the other part of its day if
+/// it is known.
+pub fn check(solver: &dyn Solver, answer: &str, other: Option<&str>) -> Vec<Warning> {
+    let mut warnings = vec![];
+    let answer = answer.trim();
+    if answer.is_empty() {
+        return vec![Warning::Empty];
+    }
+    if !solver.text_answer() {
+        let digits = answer.strip_prefix('-').unwrap_or(answer);
+        if answer.contains('\n') {
+            warnings.push(Warning::MultiLine);
+        } else if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            warnings.push(Warning::NotANumber);
+        } else {
+            if digits.bytes().all(|b| b == b'0') {
+                warnings.push(Warning::Zero);
+            } else if digits.len() < answer.len() {
+                warnings.push(Warning::Negative);
+            }
+            if looks_like_overflow(digits) {
+                warnings.push(Warning::Overflow);
+            }
+        }
+    }
+    if other.is_some_and(|o| o.trim() == answer) {
+        warnings.push(Warning::SameAsOtherPart);
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check, Warning};
+    use crate::solver::{Day, Part, Registry, Year};
+
+    #[test]
+    fn flags_suspicious_answers() {
+        let registry = Registry::new();
+        let year = Year::new(2022).unwrap();
+        let solver = |day, part| registry.get(year, Day::new(day).unwrap(), part).unwrap();
+        let number = solver(1, Part::One);
+        assert_eq!(check(number, "24000", None), vec![]);
+        assert_eq!(check(number, "", None), vec![Warning::Empty]);
+        assert_eq!(check(number, "0", None), vec![Warning::Zero]);
+        assert_eq!(check(number, "-12", None), vec![Warning::Negative]);
+        assert_eq!(check(number, "4294967295", None), vec![Warning::Overflow]);
+        assert_eq!(
+            check(number, "18446744073709551610", None),
+            vec![Warning::Overflow]
+        );
+        assert_eq!(check(number, "1\n2", None), vec![Warning::MultiLine]);
+        assert_eq!(check(number, "CMZ", None), vec![Warning::NotANumber]);
+        assert_eq!(
+            check(solver(1, Part::Two), "45000", Some("45000")),
+            vec![Warning::SameAsOtherPart]
+        );
+        assert_eq!(check(solver(5, Part::Two), "MCD", None), vec![]);
+    }
+}
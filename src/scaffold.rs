@@ -0,0 +1,143 @@
+use crate::input::input_path;
+use crate::solver::{Day, Year};
+use anyhow::{anyhow, Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str = r#"use crate::parse::lines;
+use anyhow::{anyhow, Result};
+
+pub struct Input(Vec<String>);
+
+pub fn parse(inp: &str) -> Result<Input> {
+    Ok(Input(lines(inp, |l| Ok(l.to_string()))?))
+}
+
+pub fn first(inp: &Input) -> Result<String> {
+    Err(anyhow!("not implemented, input has {} lines", inp.0.len()))
+}
+
+pub fn second(inp: &Input) -> Result<String> {
+    Err(anyhow!("not implemented, input has {} lines", inp.0.len()))
+}
+"#;
+
+/// Creates the module, inputs and example answer files of a new day and registers it
+/// with its year module, returning the created files.
+pub fn new_day(
+    root: &Path,
+    inputs: &Path,
+    year: Year,
+    day: Day,
+    title: &str,
+) -> Result<Vec<PathBuf>> {
+    let year_mod = root.join(format!("src/aoc{year}.rs"));
+    let year_src = fs::read_to_string(&year_mod)
+        .with_context(|| format!("no year module {}", year_mod.display()))?;
+    let day_mod = root.join(format!("src/aoc{year}/day{day}.rs"));
+    if day_mod.exists() {
+        return Err(anyhow!("{} already exists", day_mod.display()));
+    }
+    let year_src = register(&year_src, day, title)?;
+
+    let test_input = input_path(inputs, year, day, true);
+    let test_stem = test_input.with_extension("");
+    let mut created = vec![day_mod];
+    created.push(input_path(inputs, year, day, false));
+    created.push(test_input.clone());
+    created.extend(["part1", "part2"].map(|p| test_stem.with_extension(p)));
+    if let Some(existing) = created.iter().find(|p| p.exists()) {
+        return Err(anyhow!("{} already exists", existing.display()));
+    }
+
+    fs::create_dir_all(root.join(format!("src/aoc{year}")))?;
+    fs::create_dir_all(inputs)?;
+    let res = create_files(&created).and_then(|()| {
+        fs::write(&year_mod, year_src)
+            .with_context(|| format!("can't register the day in {}", year_mod.display()))
+    });
+    if let Err(e) = res {
+        // leftovers would make a retry fail with "already exists"
+        for pth in &created {
+            let _ = fs::remove_file(pth);
+        }
+        return Err(e);
+    }
+    Ok(created)
+}
+
+/// Creates the files of a day, the first one being its module, without replacing any.
+fn create_files(files: &[PathBuf]) -> Result<()> {
+    for (i, pth) in files.iter().enumerate() {
+        let mut f = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(pth)
+            .with_context(|| format!("can't create {}", pth.display()))?;
+        if i == 0 {
+            f.write_all(TEMPLATE.as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+/// Adds the `mod` declaration and `solvers!` entry of a day to a year module's source.
+fn register(src: &str, day: Day, title: &str) -> Result<String> {
+    let mut lines: Vec<String> = src.lines().map(str::to_string).collect();
+    let module = format!("day{day}");
+    if lines
+        .iter()
+        .any(|l| l.trim_end_matches(';').ends_with(&format!(" {module}")))
+    {
+        return Err(anyhow!("{module} is already declared"));
+    }
+    let solvers_idx = lines
+        .iter()
+        .position(|l| l.starts_with("solvers!"))
+        .ok_or_else(|| anyhow!("no solvers! invocation found"))?;
+    let close_idx = lines[solvers_idx..]
+        .iter()
+        .position(|l| l.starts_with('}'))
+        .map(|i| i + solvers_idx)
+        .ok_or_else(|| anyhow!("unterminated solvers! invocation"))?;
+    lines.insert(close_idx, format!("    {day} => {module}, {title:?};"));
+    match lines
+        .iter()
+        .rposition(|l| l.starts_with("mod day") || l.starts_with("pub mod day"))
+    {
+        Some(idx) => lines.insert(idx + 1, format!("mod {module};")),
+        None => lines
+            .splice(
+                solvers_idx..solvers_idx,
+                [format!("mod {module};"), String::new()],
+            )
+            .for_each(drop),
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::register;
+    use crate::solver::Day;
+
+    #[test]
+    fn register_first_day() {
+        let src = "use crate::solver::solvers;\n\nsolvers! { 2023;\n}\n";
+        assert_eq!(
+            register(src, Day::new(1).unwrap(), "Trebuchet?!").unwrap(),
+            "use crate::solver::solvers;\n\nmod day1;\n\nsolvers! { 2023;\n    1 => day1, \"Trebuchet?!\";\n}\n"
+        );
+    }
+
+    #[test]
+    fn register_next_day() {
+        let src = "use crate::solver::solvers;\nmod day1;\n\nsolvers! { 2023;\n    1 => day1, \"Trebuchet?!\";\n}\n";
+        assert_eq!(
+            register(src, Day::new(2).unwrap(), "Cube Conundrum").unwrap(),
+            "use crate::solver::solvers;\nmod day1;\nmod day2;\n\nsolvers! { 2023;\n    1 => day1, \"Trebuchet?!\";\n    2 => day2, \"Cube Conundrum\";\n}\n"
+        );
+        assert!(register(src, Day::new(1).unwrap(), "Again").is_err());
+    }
+}
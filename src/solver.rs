@@ -0,0 +1,419 @@
+use crate::error::AocError;
+use crate::generate::{Generator, Rng};
+use crate::input::{normalize, Whitespace};
+use crate::parse::ParseError;
+use anyhow::{anyhow, Result};
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Year(u16);
+
+impl Year {
+    pub fn new(year: u16) -> Result<Self> {
+        if year < 2015 {
+            return Err(anyhow!(
+                "illegal year {year}, advent of code started in 2015"
+            ));
+        }
+        Ok(Self(year))
+    }
+
+    pub fn get(&self) -> u16 {
+        self.0
+    }
+}
+
+impl FromStr for Year {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::new(s.parse().map_err(|_| anyhow!("illegal year '{s}'"))?)
+    }
+}
+
+impl Display for Year {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Day(u8);
+
+impl Day {
+    pub fn new(day: u8) -> Result<Self> {
+        if !(1..=25).contains(&day) {
+            return Err(anyhow!("illegal day {day}, must be within 1..=25"));
+        }
+        Ok(Self(day))
+    }
+
+    pub fn get(&self) -> u8 {
+        self.0
+    }
+}
+
+impl FromStr for Day {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::new(s.parse().map_err(|_| anyhow!("illegal day '{s}'"))?)
+    }
+}
+
+impl Display for Day {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn get(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(anyhow!("illegal part '{s}', must be 1 or 2")),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.get())
+    }
+}
+
+/// Input parsed by [`Solver::parse`], shared between both parts of a day.
+pub type Parsed = Box<dyn Any>;
+
+/// A single part of a single day's puzzle.
+pub trait Solver: Send + Sync {
+    fn year(&self) -> Year;
+    fn day(&self) -> Day;
+    fn part(&self) -> Part;
+    fn title(&self) -> &'static str;
+    fn parse(&self, inp: &str) -> Result<Parsed>;
+    /// Solves this part against input parsed by either part of the same day.
+    fn solve_parsed(&self, parsed: &Parsed) -> Result<String>;
+    /// Whether this part answers with text, like letters drawn on a screen, not a number.
+    fn text_answer(&self) -> bool {
+        false
+    }
+    /// Checks the input without parsing it, `None` if the day has no validator.
+    fn validate(&self, _inp: &str) -> Option<Vec<ParseError>> {
+        None
+    }
+    /// Generates a valid input of roughly `size` bytes from `seed`, to see how the solver
+    /// scales. `None` if the day has no generator.
+    fn generate(&self, _seed: u64, _size: usize) -> Option<String> {
+        None
+    }
+
+    fn solve(&self, inp: &str) -> Result<String> {
+        self.solve_parsed(&self.parse(inp)?)
+    }
+}
+
+/// Adapts the `parse`, `first` and `second` functions of a day module to a [`Solver`].
+pub struct FnSolver<I> {
+    pub year: Year,
+    pub day: Day,
+    pub part: Part,
+    pub title: &'static str,
+    pub whitespace: Whitespace,
+    pub validate: Option<fn(&str) -> Vec<ParseError>>,
+    pub generate: Option<Generator>,
+    /// The parts answering with text instead of a number.
+    pub text: &'static [Part],
+    pub parse: fn(&str) -> Result<I>,
+    pub func: fn(&I) -> Result<String>,
+}
+
+impl<I: 'static> Solver for FnSolver<I> {
+    fn year(&self) -> Year {
+        self.year
+    }
+
+    fn day(&self) -> Day {
+        self.day
+    }
+
+    fn part(&self) -> Part {
+        self.part
+    }
+
+    fn title(&self) -> &'static str {
+        self.title
+    }
+
+    fn parse(&self, inp: &str) -> Result<Parsed> {
+        let inp = normalize(inp, self.whitespace);
+        let inp = inp.as_ref();
+        match (self.parse)(inp) {
+            Ok(parsed) => Ok(Box::new(parsed)),
+            Err(e) => Err(AocError::Parse(match e.downcast::<ParseError>() {
+                Ok(e) => e.within(inp).into(),
+                Err(e) => e,
+            })
+            .into()),
+        }
+    }
+
+    fn solve_parsed(&self, parsed: &Parsed) -> Result<String> {
+        let inp = parsed.downcast_ref::<I>().ok_or_else(|| {
+            anyhow!(
+                "input for {}-{} was not parsed by that day",
+                self.year,
+                self.day
+            )
+        })?;
+        (self.func)(inp)
+    }
+
+    fn text_answer(&self) -> bool {
+        self.text.contains(&self.part)
+    }
+
+    fn validate(&self, inp: &str) -> Option<Vec<ParseError>> {
+        let validate = self.validate?;
+        let inp = normalize(inp, self.whitespace);
+        Some(validate(&inp).into_iter().map(|e| e.within(&inp)).collect())
+    }
+
+    fn generate(&self, seed: u64, size: usize) -> Option<String> {
+        Some((self.generate?)(&mut Rng::new(seed), size))
+    }
+}
+
+/// Both parts of a day, the second sharing everything but its function with the first.
+pub fn day_solvers<I: 'static>(
+    first: FnSolver<I>,
+    second: fn(&I) -> Result<String>,
+) -> [Box<dyn Solver>; 2] {
+    let second = FnSolver {
+        part: Part::Two,
+        func: second,
+        ..first
+    };
+    [Box::new(first), Box::new(second)]
+}
+
+/// Registers `parse`, `first` and `second` of each listed day module for the given year.
+///
+/// Inputs are normalized before parsing, a day can keep their whitespace as is with a
+/// trailing `, whitespace = Keep`. A day with a function checking its input for `aoc
+/// validate` declares it with `, validate = dayN::validate`, one generating inputs for the
+/// scaling benchmarks with `, generate = dayN::generate`, and parts answering with text
+/// rather than a number are listed like `, text = [One, Two]`.
+macro_rules! solvers {
+    (@whitespace) => {
+        $crate::input::Whitespace::Trim
+    };
+    (@whitespace $whitespace:ident) => {
+        $crate::input::Whitespace::$whitespace
+    };
+    (@validate) => {
+        None
+    };
+    (@validate $validate:path) => {
+        Some($validate)
+    };
+    (@generate) => {
+        None
+    };
+    (@generate $generate:path) => {
+        Some($generate)
+    };
+    ($year:literal; $(
+        $day:literal => $module:ident, $title:literal
+        $(, whitespace = $whitespace:ident)?
+        $(, validate = $validate:path)?
+        $(, generate = $generate:path)?
+        $(, text = [$($text:ident),*])?;
+    )*) => {
+        pub fn solvers() -> Vec<Box<dyn $crate::solver::Solver>> {
+            use $crate::solver::Solver;
+            let days: Vec<[Box<dyn Solver>; 2]> = vec![$($crate::solver::day_solvers(
+                $crate::solver::FnSolver {
+                    year: $crate::solver::Year::new($year).unwrap(),
+                    day: $crate::solver::Day::new($day).unwrap(),
+                    part: $crate::solver::Part::One,
+                    title: $title,
+                    whitespace: $crate::solver::solvers!(@whitespace $($whitespace)?),
+                    validate: $crate::solver::solvers!(@validate $($validate)?),
+                    generate: $crate::solver::solvers!(@generate $($generate)?),
+                    text: &[$($($crate::solver::Part::$text),*)?],
+                    parse: $module::parse,
+                    func: $module::first,
+                },
+                $module::second,
+            )),*];
+            days.into_iter().flatten().collect()
+        }
+    };
+}
+pub(crate) use solvers;
+
+/// Every registered solver, built once and kept for the whole process so runs can hand
+/// them to worker threads that may outlive the run.
+fn all_solvers() -> &'static [Box<dyn Solver>] {
+    static SOLVERS: OnceLock<Vec<Box<dyn Solver>>> = OnceLock::new();
+    SOLVERS.get_or_init(|| {
+        let mut solvers: Vec<_> = crate::YEARS.iter().flat_map(|(_, s)| s()).collect();
+        solvers.sort_by_key(|s| (s.year(), s.day(), s.part()));
+        solvers
+    })
+}
+
+pub struct Registry {
+    years: Vec<Year>,
+    solvers: &'static [Box<dyn Solver>],
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        let years = crate::YEARS
+            .iter()
+            .map(|(y, _)| Year::new(*y).unwrap())
+            .collect();
+        Self {
+            years,
+            solvers: all_solvers(),
+        }
+    }
+
+    pub fn years(&self) -> &[Year] {
+        &self.years
+    }
+
+    pub fn solvers(&self) -> impl Iterator<Item = &'static dyn Solver> {
+        self.solvers.iter().map(|s| s.as_ref())
+    }
+
+    pub fn year(
+        &self,
+        year: Year,
+    ) -> std::result::Result<impl Iterator<Item = &'static dyn Solver>, AocError> {
+        if !self.years.contains(&year) {
+            return Err(AocError::UnknownYear {
+                year,
+                available: self.years.clone(),
+            });
+        }
+        Ok(self.solvers().filter(move |s| s.year() == year))
+    }
+
+    pub fn get(
+        &self,
+        year: Year,
+        day: Day,
+        part: Part,
+    ) -> std::result::Result<&'static dyn Solver, AocError> {
+        self.year(year)?
+            .find(|s| s.day() == day && s.part() == part)
+            .ok_or(AocError::UnknownPuzzle { year, day, part })
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Day, Part, Registry, Year};
+
+    #[test]
+    fn unknown_year_lists_available_years() {
+        let err = Registry::new()
+            .get(Year::new(2021).unwrap(), Day::new(3).unwrap(), Part::One)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "unknown year 2021, available years are: 2022, 2023"
+        );
+    }
+
+    #[test]
+    fn known_year_without_solver() {
+        let err = Registry::new()
+            .get(Year::new(2023).unwrap(), Day::new(1).unwrap(), Part::One)
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "puzzle 2023-1-1 is not implemented");
+    }
+
+    #[test]
+    fn validates_declared_days_only() {
+        let registry = Registry::new();
+        let year = Year::new(2022).unwrap();
+        let solver = |day| {
+            registry
+                .get(year, Day::new(day).unwrap(), Part::One)
+                .unwrap()
+        };
+        let errs = solver(4).validate("2-4,6-8\r\n4-2,1-1\r\n").unwrap();
+        assert_eq!(
+            errs.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["line 2, column 1: range ends before it starts"]
+        );
+        assert!(solver(1).validate("1\n").is_none());
+    }
+
+    #[test]
+    fn moves_take_at_most_a_stack() {
+        let registry = Registry::new();
+        let solver = registry
+            .get(Year::new(2022).unwrap(), Day::new(5).unwrap(), Part::Two)
+            .unwrap();
+        let inp = "[A]    \n[B] [C]\n 1   2 \n\nmove 1 from 2 to 1\nmove 4 from 1 to 2\n";
+        let msg = "line 6, column 6: stack 1 only holds 3 crates";
+        let err = solver.solve(inp).unwrap_err();
+        assert_eq!(err.to_string(), format!("failed to parse input: {msg}"));
+        let errs = solver.validate(inp).unwrap();
+        assert_eq!(
+            errs.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![msg]
+        );
+    }
+
+    #[test]
+    fn locates_directories_that_are_not_there() {
+        let registry = Registry::new();
+        let solver = registry
+            .get(Year::new(2022).unwrap(), Day::new(7).unwrap(), Part::One)
+            .unwrap();
+        let err = solver
+            .solve("$ cd /\n$ ls\ndir a\n1 b\n$ cd b\n")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse input: line 5, column 6: no directory 'b' here"
+        );
+    }
+}
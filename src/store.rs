@@ -0,0 +1,201 @@
+use crate::crypt::ENCRYPTED_EXT;
+use crate::input::{input_path, read_file};
+use crate::manifest::Kind;
+use crate::solver::{Day, Year};
+use anyhow::{anyhow, Result};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// A file of an [`InputStore`], `YYYY-D.txt` for a personal input or
+/// `YYYY-D-test[-name].txt` for an example.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct InputFile {
+    pub name: String,
+    pub year: Year,
+    pub day: Day,
+    pub kind: Kind,
+}
+
+impl FromStr for InputFile {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> std::result::Result<Self, Self::Err> {
+        let illegal = || anyhow!("illegal input file name '{name}', expected YYYY-D.txt");
+        let (year, rest) = name
+            .strip_suffix(".txt")
+            .and_then(|s| s.split_once('-'))
+            .ok_or_else(illegal)?;
+        let day = match rest.split_once('-') {
+            Some((day, example)) if example.starts_with("test") => day,
+            Some(_) => return Err(illegal()),
+            None => rest,
+        };
+        Ok(Self {
+            name: name.to_string(),
+            year: year.parse()?,
+            day: day.parse()?,
+            kind: Kind::of(name),
+        })
+    }
+}
+
+/// A file [`InputStore::files`] left out, as it can't be an input or example.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Skipped {
+    pub name: String,
+    pub reason: String,
+}
+
+impl Display for Skipped {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "skipping {}: {}", self.name, self.reason)
+    }
+}
+
+/// The directory holding the inputs and examples of every year, the one place that knows
+/// how their files are named and found.
+#[derive(Debug, Clone)]
+pub struct InputStore {
+    dir: PathBuf,
+}
+
+impl InputStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path(&self, year: Year, day: Day, test: bool) -> PathBuf {
+        input_path(&self.dir, year, day, test)
+    }
+
+    /// Reads the input of a day, `None` if there is no input file for it.
+    pub fn read(&self, year: Year, day: Day, test: bool) -> Result<Option<String>> {
+        read_file(&self.path(year, day, test))
+    }
+
+    /// Reads a file listed by [`InputStore::files`], `None` if it was removed since.
+    pub fn read_file(&self, file: &InputFile) -> Result<Option<String>> {
+        read_file(&self.dir.join(&file.name))
+    }
+
+    /// The names of the `.txt` files, sorted, none if the directory doesn't exist.
+    /// Encrypted files are listed by the name they have when decrypted, files whose names
+    /// aren't valid UTF-8 are left out, [`InputStore::files`] reports them.
+    pub fn file_names(&self) -> Result<Vec<String>> {
+        Ok(self.scan()?.0)
+    }
+
+    /// The `.txt` file names along with the files skipped for not having a UTF-8 name.
+    fn scan(&self) -> Result<(Vec<String>, Vec<Skipped>)> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok((vec![], vec![])),
+            Err(e) => return Err(e.into()),
+        };
+        let (mut names, mut skipped) = (vec![], vec![]);
+        for entry in entries {
+            let name = match entry?.file_name().into_string() {
+                Ok(name) => name,
+                Err(name) => {
+                    skipped.push(Skipped {
+                        name: self.dir.join(name).display().to_string(),
+                        reason: "the name is not valid UTF-8".to_string(),
+                    });
+                    continue;
+                }
+            };
+            let name = name
+                .strip_suffix(&format!(".{ENCRYPTED_EXT}"))
+                .map_or(name.clone(), str::to_string);
+            if name.ends_with(".txt") {
+                names.push(name);
+            }
+        }
+        names.sort();
+        names.dedup();
+        Ok((names, skipped))
+    }
+
+    /// Every input and example file, sorted by year, day and name, along with the files
+    /// that were skipped as they don't follow the naming convention, for the caller to
+    /// report.
+    pub fn files(&self) -> Result<(Vec<InputFile>, Vec<Skipped>)> {
+        let (names, mut skipped) = self.scan()?;
+        let mut files = vec![];
+        for name in names {
+            match name.parse::<InputFile>() {
+                Ok(file) => files.push(file),
+                Err(e) => skipped.push(Skipped {
+                    name: self.dir.join(&name).display().to_string(),
+                    reason: format!("{e:#}"),
+                }),
+            }
+        }
+        files.sort_by(|a, b| (a.year, a.day, &a.name).cmp(&(b.year, b.day, &b.name)));
+        Ok((files, skipped))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{InputFile, InputStore};
+    use crate::manifest::Kind;
+    use crate::test_dir::TestDir;
+    use std::fs;
+
+    #[test]
+    fn parse_file_names() {
+        let file: InputFile = "2022-7.txt".parse().unwrap();
+        assert_eq!((file.year.get(), file.day.get()), (2022, 7));
+        assert_eq!(file.kind, Kind::Input);
+        let file: InputFile = "2022-5-test-crlf.txt".parse().unwrap();
+        assert_eq!((file.day.get(), file.kind), (5, Kind::Example));
+        assert!("2022-7-old.txt".parse::<InputFile>().is_err());
+        assert!("2022-26.txt".parse::<InputFile>().is_err());
+        assert!("notes.txt".parse::<InputFile>().is_err());
+    }
+
+    #[test]
+    fn lists_files_by_day() {
+        let dir = TestDir::new("store");
+        for name in [
+            "2022-10.txt",
+            "2022-9.txt",
+            "2022-9-test.txt",
+            "notes.txt",
+            "a.toml",
+        ] {
+            fs::write(dir.join(name), "1\n").unwrap();
+        }
+        fs::write(dir.join("2022-1.txt.enc"), "").unwrap();
+        let (files, skipped) = InputStore::new(&*dir).files().unwrap();
+        let names: Vec<_> = files.into_iter().map(|f| f.name).collect();
+        assert_eq!(
+            names,
+            ["2022-1.txt", "2022-9-test.txt", "2022-9.txt", "2022-10.txt"]
+        );
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].name.ends_with("notes.txt"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reports_names_that_are_not_utf8() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = TestDir::new("store-utf8");
+        fs::write(dir.join(OsStr::from_bytes(b"2022-\xff.txt")), "1\n").unwrap();
+        let store = InputStore::new(&*dir);
+        assert!(store.file_names().unwrap().is_empty());
+        let (_, skipped) = store.files().unwrap();
+        assert_eq!(skipped[0].reason, "the name is not valid UTF-8");
+    }
+}
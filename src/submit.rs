@@ -0,0 +1,293 @@
+use crate::solver::{Day, Part, Year};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const HISTORY_FILE: &str = "history.toml";
+
+/// The site's verdict on a submitted answer.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reply {
+    Correct,
+    TooHigh,
+    TooLow,
+    Incorrect,
+    RateLimited,
+    /// The part was already solved, or part 1 is still missing.
+    WrongLevel,
+}
+
+impl Reply {
+    /// Parses the article of the answer page, along with the wait for rate limited replies.
+    pub fn parse(html: &str) -> Result<(Self, Option<Duration>)> {
+        if html.contains("That's the right answer") {
+            Ok((Reply::Correct, None))
+        } else if html.contains("That's not the right answer") {
+            if html.contains("your answer is too high") {
+                Ok((Reply::TooHigh, None))
+            } else if html.contains("your answer is too low") {
+                Ok((Reply::TooLow, None))
+            } else {
+                Ok((Reply::Incorrect, None))
+            }
+        } else if html.contains("You gave an answer too recently") {
+            Ok((Reply::RateLimited, parse_wait(html)))
+        } else if html.contains("You don't seem to be solving the right level") {
+            Ok((Reply::WrongLevel, None))
+        } else {
+            Err(anyhow!("unrecognized answer response"))
+        }
+    }
+
+    fn is_wrong(&self) -> bool {
+        matches!(self, Reply::TooHigh | Reply::TooLow | Reply::Incorrect)
+    }
+}
+
+impl Display for Reply {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Reply::Correct => "correct",
+            Reply::TooHigh => "too high",
+            Reply::TooLow => "too low",
+            Reply::Incorrect => "incorrect",
+            Reply::RateLimited => "rate limited",
+            Reply::WrongLevel => "wrong level",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Parses the wait out of "You have 1m 5s left to wait".
+fn parse_wait(html: &str) -> Option<Duration> {
+    let (_, rest) = html.split_once("You have ")?;
+    let (wait, _) = rest.split_once(" left to wait")?;
+    wait.split_whitespace().try_fold(Duration::ZERO, |sum, w| {
+        let secs: u64 = match w.as_bytes().last()? {
+            b'm' => w[..w.len() - 1].parse::<u64>().ok()? * 60,
+            b's' => w[..w.len() - 1].parse().ok()?,
+            _ => return None,
+        };
+        Some(sum + Duration::from_secs(secs))
+    })
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Attempt {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub reply: Reply,
+    /// Seconds since the unix epoch.
+    pub time: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait_secs: Option<u64>,
+}
+
+impl Attempt {
+    pub fn new(year: Year, day: Day, part: Part, answer: &str, reply: Reply) -> Self {
+        Self {
+            year: year.get(),
+            day: day.get(),
+            part: part.get(),
+            answer: answer.to_string(),
+            reply,
+            time: now(),
+            wait_secs: None,
+        }
+    }
+
+    fn is_for(&self, year: Year, day: Day, part: Part) -> bool {
+        self.year == year.get() && self.day == day.get() && self.part == part.get()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Every answer submitted so far, stored in `answers/history.toml`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    #[serde(default, rename = "attempt")]
+    attempts: Vec<Attempt>,
+}
+
+impl History {
+    pub fn load(pth: &Path) -> Result<Self> {
+        match fs::read_to_string(pth) {
+            Ok(s) => toml::from_str(&s)
+                .with_context(|| format!("illegal history file {}", pth.display())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, pth: &Path) -> Result<()> {
+        if let Some(dir) = pth.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(pth, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, attempt: Attempt) {
+        self.attempts.push(attempt);
+    }
+
+    pub fn attempts(&self, year: Year, day: Day, part: Part) -> impl Iterator<Item = &Attempt> {
+        self.attempts
+            .iter()
+            .filter(move |a| a.is_for(year, day, part))
+    }
+
+    /// Refuses answers that are known to be wrong from earlier attempts or that are
+    /// outside the bounds given by earlier too high and too low replies.
+    pub fn check(&self, year: Year, day: Day, part: Part, answer: &str) -> Result<()> {
+        let attempts: Vec<_> = self.attempts(year, day, part).collect();
+        if let Some(correct) = attempts.iter().find(|a| a.reply == Reply::Correct) {
+            return Err(anyhow!(
+                "{year}-{day}-{part} was already solved with '{}'",
+                correct.answer
+            ));
+        }
+        if let Some(wrong) = attempts
+            .iter()
+            .find(|a| a.answer == answer && a.reply.is_wrong())
+        {
+            return Err(anyhow!(
+                "'{answer}' was already submitted and was {}",
+                wrong.reply
+            ));
+        }
+        if let Some(limited) = attempts.last().filter(|a| a.reply == Reply::RateLimited) {
+            let until = limited.time + limited.wait_secs.unwrap_or(60);
+            if until > now() {
+                return Err(anyhow!("rate limited for another {}s", until - now()));
+            }
+        }
+        if let Ok(value) = answer.parse::<i128>() {
+            let bound = |reply| {
+                attempts
+                    .iter()
+                    .filter(move |a| a.reply == reply)
+                    .filter_map(|a| a.answer.parse::<i128>().ok())
+            };
+            if let Some(high) = bound(Reply::TooHigh).min().filter(|h| value >= *h) {
+                return Err(anyhow!("{answer} is not below {high}, which was too high"));
+            }
+            if let Some(low) = bound(Reply::TooLow).max().filter(|l| value <= *l) {
+                return Err(anyhow!("{answer} is not above {low}, which was too low"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Attempt, History, Reply};
+    use crate::client::Client;
+    use crate::fake_aoc::FakeAoc;
+    use crate::solver::{Day, Part, Year};
+    use crate::test_dir::TestDir;
+    use std::time::Duration;
+
+    #[test]
+    fn parse_replies() {
+        let parse = |s| Reply::parse(s).unwrap();
+        assert_eq!(
+            parse(
+                "<article><p>That's the right answer!  You are one gold star closer.</p></article>"
+            ),
+            (Reply::Correct, None)
+        );
+        assert_eq!(
+            parse("<p>That's not the right answer; your answer is too high.  Please wait one minute before trying again.</p>"),
+            (Reply::TooHigh, None)
+        );
+        assert_eq!(
+            parse("<p>That's not the right answer; your answer is too low.</p>"),
+            (Reply::TooLow, None)
+        );
+        assert_eq!(
+            parse("<p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait.</p>"),
+            (Reply::RateLimited, Some(Duration::from_secs(65)))
+        );
+        assert_eq!(
+            parse("<p>You don't seem to be solving the right level.  Did you already complete it?</p>"),
+            (Reply::WrongLevel, None)
+        );
+        assert!(Reply::parse("<html></html>").is_err());
+    }
+
+    #[test]
+    fn refuse_known_wrong_and_out_of_bounds() {
+        let (year, day, part) = (Year::new(2022).unwrap(), Day::new(7).unwrap(), Part::Two);
+        let mut history = History::default();
+        history.record(Attempt::new(year, day, part, "100", Reply::TooHigh));
+        history.record(Attempt::new(year, day, part, "10", Reply::TooLow));
+        history.record(Attempt::new(year, day, part, "42", Reply::Incorrect));
+
+        assert!(history.check(year, day, part, "42").is_err());
+        assert!(history.check(year, day, part, "150").is_err());
+        assert!(history.check(year, day, part, "100").is_err());
+        assert!(history.check(year, day, part, "5").is_err());
+        assert!(history.check(year, day, part, "50").is_ok());
+        assert!(history.check(year, day, Part::One, "150").is_ok());
+
+        history.record(Attempt::new(year, day, part, "50", Reply::Correct));
+        assert!(history.check(year, day, part, "51").is_err());
+    }
+
+    #[test]
+    fn history_roundtrip() {
+        let dir = TestDir::new("history");
+        let pth = dir.join("history.toml");
+        let mut history = History::load(&pth).unwrap();
+        let (year, day) = (Year::new(2022).unwrap(), Day::new(7).unwrap());
+        let mut limited = Attempt::new(year, day, Part::One, "1", Reply::RateLimited);
+        limited.wait_secs = Some(30);
+        history.record(limited.clone());
+        history.save(&pth).unwrap();
+
+        let loaded = History::load(&pth).unwrap();
+        assert_eq!(
+            loaded.attempts(year, day, Part::One).collect::<Vec<_>>(),
+            vec![&limited]
+        );
+        assert!(loaded.check(year, day, Part::One, "2").is_err());
+    }
+
+    #[test]
+    fn submit_to_fake_server() {
+        let server = FakeAoc::start("secret");
+        server.set_answer("/2022/day/7/answer", 2, "8319096");
+        let client = Client::new(&server.url(), "secret");
+        let (year, day) = (Year::new(2022).unwrap(), Day::new(7).unwrap());
+
+        let submit = |answer| client.submit(year, day, Part::Two, answer).unwrap();
+        assert_eq!(submit("9000000"), (Reply::TooHigh, None));
+        assert_eq!(submit("8000000"), (Reply::TooLow, None));
+        assert_eq!(submit("8319096"), (Reply::Correct, None));
+        assert_eq!(submit("8319096"), (Reply::WrongLevel, None));
+
+        let requests = server.requests();
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].body, "level=2&answer=9000000");
+
+        server.rate_limit(Duration::from_secs(34));
+        assert_eq!(
+            submit("1"),
+            (Reply::RateLimited, Some(Duration::from_secs(34)))
+        );
+    }
+}
@@ -1 +0,0 @@
-pub mod utils;
\ No newline at end of file